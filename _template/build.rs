@@ -0,0 +1,3 @@
+fn main() {
+    common::readme::write_example_tests("Readme.md");
+}
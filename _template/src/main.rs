@@ -1,31 +1,64 @@
+use common::input::InputFiles;
+use common::parse::ParseError;
+
 fn main() {
-    println!("Part 1: {}", part1(common::read_lines("./input1.txt")));
-    println!("Part 2: {}", part2(common::read_lines("./input2.txt")));
+    let files = InputFiles::new(env!("CARGO_MANIFEST_DIR"), "input.txt", "input.txt");
+
+    if common::bench::requested() {
+        bench(&files);
+        return;
+    }
+
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let paths = common::input::take_paths(&mut args).unwrap_or_else(|err| {
+        eprintln!("error: {}", err);
+        std::process::exit(1);
+    });
+
+    let solved = files.run(&paths, |input1, input2| -> Result<(), ParseError> {
+        let part1 = part1(input1.lines.clone()).map_err(|err| err.in_file(&input1.name))?;
+        println!("Part 1: {}", part1);
+        let part2 = part2(input2.lines.clone()).map_err(|err| err.in_file(&input2.name))?;
+        println!("Part 2: {}", part2);
+        Ok(())
+    });
+    if !solved {
+        std::process::exit(1);
+    }
+}
+
+fn bench(files: &InputFiles) {
+    let (input, _) = files.read_lines();
+
+    print!(
+        "{}",
+        common::bench::Bench::new()
+            .run("parse", || get_input(&input))
+            .run("part1", || part1(input.clone()))
+            .run("part2", || part2(input.clone()))
+            .report()
+    );
 }
 
-fn part1(input: Vec<String>) -> u64 {
-    0
+// Parse with common::parse, its errors point at the offending line and column. --bench times
+// this as the parse step.
+fn get_input(input: &[String]) -> Result<Vec<String>, ParseError> {
+    Ok(input.to_vec())
 }
 
 #[allow(unused_variables)]
-fn part2(input: Vec<String>) -> u64 {
-    0
+fn part1(input: Vec<String>) -> Result<u64, ParseError> {
+    let input = get_input(&input)?;
+    Ok(0)
+}
+
+#[allow(unused_variables)]
+fn part2(input: Vec<String>) -> Result<u64, ParseError> {
+    let input = get_input(&input)?;
+    Ok(0)
 }
 
 #[cfg(test)]
 mod tests {
-
-    fn get_test_input() -> Vec<String> {
-        vec!["".to_string()]
-    }
-
-    #[test]
-    fn test_part1() {
-        assert_eq!(super::part1(get_test_input()), 0);
-    }
-
-    #[test]
-    fn test_part2() {
-        assert_eq!(super::part2(get_test_input()), 0);
-    }
+    include!(concat!(env!("OUT_DIR"), "/readme_examples.rs"));
 }
@@ -0,0 +1,28 @@
+use std::fmt::Write;
+
+// One #[test] per entry of answers.toml, included by the tests of main.rs.
+fn main() {
+    println!("cargo:rerun-if-changed=../answers.toml");
+
+    let text = std::fs::read_to_string("../answers.toml").unwrap_or_default();
+    let answers: toml::Table = text.parse().expect("answers.toml is not valid toml");
+
+    let mut tests = String::new();
+    for (day, parts) in answers.iter() {
+        for (part, answer) in parts.as_table().into_iter().flatten() {
+            let answer = match answer {
+                toml::Value::String(answer) => answer.clone(),
+                answer => answer.to_string(),
+            };
+            writeln!(
+                tests,
+                "#[test]\nfn {}_{}() {{\n    check_answer({:?}, {:?}, {:?});\n}}\n",
+                day, part, day, part, answer
+            )
+            .unwrap();
+        }
+    }
+
+    let out = std::path::Path::new(&std::env::var("OUT_DIR").unwrap()).join("answer_tests.rs");
+    std::fs::write(out, tests).unwrap();
+}
@@ -0,0 +1,1215 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Display;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use common::fetch::{FetchError, Fetcher};
+use common::submit::Verdict;
+
+// A step is only reported as faster or slower if it moved by more than this many percent.
+const DEFAULT_THRESHOLD: f64 = 10.0;
+const BASELINE_FILE: &str = "benchmarks.tsv";
+const ANSWERS_FILE: &str = "answers.toml";
+const TEMPLATE_DIR: &str = "_template";
+
+#[derive(Debug, PartialEq)]
+enum AocError {
+    Usage,
+    Io { path: String, message: String },
+    Day { day: String, message: String },
+    Baseline { line: usize, text: String },
+    Answers(String),
+    Verify { failed: usize },
+    Fetch(FetchError),
+    Submit(Verdict),
+}
+
+impl Display for AocError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AocError::Usage => write!(
+                f,
+                "usage: aoc bench [day...] [--baseline file] [--save] [--threshold percent]\n       aoc verify [day...]\n       aoc new <day> [--readme file]\n       aoc fetch <day> [--year year]\n       aoc submit <day> <part> [--answer answer] [--year year]\n       aoc run <day> [file...]"
+            ),
+            AocError::Io { path, message } => write!(f, "{}: {}", path, message),
+            AocError::Day { day, message } => write!(f, "{}: {}", day, message),
+            AocError::Baseline { line, text } => {
+                write!(f, "baseline line {}: cannot read '{}'", line, text)
+            }
+            AocError::Answers(message) => write!(f, "{}: {}", ANSWERS_FILE, message),
+            AocError::Verify { failed } => write!(f, "{} answer(s) changed", failed),
+            AocError::Fetch(err) => write!(f, "{}", err),
+            AocError::Submit(verdict) => write!(f, "{}", verdict),
+        }
+    }
+}
+
+// Median time of one step (parse, part1, ...) of one day.
+#[derive(Debug, Clone, PartialEq)]
+struct Timing {
+    day: String,
+    step: String,
+    nanos: u128,
+}
+
+#[derive(Debug, PartialEq)]
+enum Change {
+    New,
+    Same(f64),
+    Faster(f64),
+    Slower(f64),
+}
+
+// day -> part -> answer, e.g. "day4" -> "part2" -> "11827296"
+type Answers = BTreeMap<String, BTreeMap<String, String>>;
+
+// What a day printed for one part of the registry, None if it never printed that part.
+#[derive(Debug, PartialEq)]
+struct Check {
+    day: String,
+    part: String,
+    expected: String,
+    actual: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+struct SubmitOptions {
+    day: String,
+    part: u32,
+    answer: Option<String>,
+    year: Option<u32>,
+}
+
+#[derive(Debug, PartialEq)]
+struct BenchOptions {
+    days: Vec<String>,
+    baseline: Option<String>,
+    save: bool,
+    threshold: f64,
+}
+
+fn main() {
+    if let Err(err) = run() {
+        eprintln!("error: {}", err);
+        std::process::exit(1);
+    }
+}
+
+fn run() -> Result<(), AocError> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    match args.first().map(String::as_str) {
+        Some("bench") => bench(&get_root(), &get_bench_options(&args[1..])?),
+        Some("verify") => verify(&get_root(), &get_day_args(&args[1..])?),
+        Some("new") => {
+            let (day, readme) = get_new_options(&args[1..])?;
+            let dir = new_day(&get_root(), &day, readme.as_deref())?;
+            println!("created {}", dir.display());
+            Ok(())
+        }
+        Some("fetch") => {
+            let (day, year) = get_fetch_options(&args[1..])?;
+            let mut fetcher = Fetcher::from_env();
+            fetcher.year = year.unwrap_or(fetcher.year);
+            for path in fetch_day(&get_root(), &day, &fetcher)? {
+                println!("wrote {}", path.display());
+            }
+            Ok(())
+        }
+        Some("run") => {
+            let (day, paths) = get_run_options(&args[1..])?;
+            let cwd = std::env::current_dir().map_err(get_io_error(Path::new(".")))?;
+            run_inputs(&get_root(), &day, &get_input_args(&paths, &cwd))
+        }
+        Some("submit") => {
+            let options = get_submit_options(&args[1..])?;
+            let mut fetcher = Fetcher::from_env();
+            fetcher.year = options.year.unwrap_or(fetcher.year);
+            submit(&get_root(), &options, &fetcher)
+        }
+        _ => Err(AocError::Usage),
+    }
+}
+
+// The days live next to this crate.
+fn get_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .unwrap()
+        .to_path_buf()
+}
+
+// All dayN directories with a Cargo.toml, in day order.
+fn get_days(root: &Path) -> Result<Vec<String>, AocError> {
+    let entries = std::fs::read_dir(root).map_err(|err| AocError::Io {
+        path: root.display().to_string(),
+        message: err.to_string(),
+    })?;
+
+    let mut days: Vec<(u32, String)> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().join("Cargo.toml").is_file())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            Some((get_day_number(&name)?, name))
+        })
+        .collect();
+    days.sort();
+
+    Ok(days.into_iter().map(|(_, name)| name).collect())
+}
+
+fn get_day_number(day: &str) -> Option<u32> {
+    day.strip_prefix("day")?.parse::<u32>().ok()
+}
+
+// "4" and "day4" both mean day4.
+fn get_day_args(args: &[String]) -> Result<Vec<String>, AocError> {
+    args.iter()
+        .map(|arg| match arg.parse::<u32>() {
+            Ok(number) => Ok(format!("day{}", number)),
+            Err(_) if get_day_number(arg).is_some() => Ok(arg.to_string()),
+            Err(_) => Err(AocError::Usage),
+        })
+        .collect()
+}
+
+// Builds a day in release mode and returns the path of its binary.
+fn build_day(root: &Path, day: &str) -> Result<PathBuf, AocError> {
+    let dir = root.join(day);
+    let output = Command::new("cargo")
+        .args(["build", "--release", "--quiet"])
+        .current_dir(&dir)
+        .output()
+        .map_err(|err| AocError::Day {
+            day: day.to_string(),
+            message: err.to_string(),
+        })?;
+
+    if !output.status.success() {
+        return Err(AocError::Day {
+            day: day.to_string(),
+            message: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        });
+    }
+    Ok(dir.join("target").join("release").join(day))
+}
+
+// Runs a day from its own directory, so it finds its inputs.
+fn run_day(root: &Path, day: &str, args: &[&str]) -> Result<String, AocError> {
+    let output = Command::new(build_day(root, day)?)
+        .args(args)
+        .current_dir(root.join(day))
+        .output()
+        .map_err(|err| AocError::Day {
+            day: day.to_string(),
+            message: err.to_string(),
+        })?;
+
+    if !output.status.success() {
+        return Err(AocError::Day {
+            day: day.to_string(),
+            message: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        });
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+// <day> [file...]
+fn get_run_options(args: &[String]) -> Result<(String, Vec<String>), AocError> {
+    let (day, paths) = args.split_first().ok_or(AocError::Usage)?;
+    let day = get_day_args(std::slice::from_ref(day))?.remove(0);
+
+    Ok((day, paths.to_vec()))
+}
+
+// The day runs in its own directory, so relative paths are made absolute first. "-" stays stdin.
+fn get_input_args(paths: &[String], cwd: &Path) -> Vec<String> {
+    if paths.is_empty() {
+        return Vec::new();
+    }
+
+    let paths = paths.iter().map(|path| match path.as_str() {
+        "-" => path.to_string(),
+        _ => cwd.join(path).display().to_string(),
+    });
+    std::iter::once("--input".to_string())
+        .chain(paths)
+        .collect()
+}
+
+// Like run_day, but the output is passed through and stdin is the day's.
+fn run_inputs(root: &Path, day: &str, args: &[String]) -> Result<(), AocError> {
+    let status = Command::new(build_day(root, day)?)
+        .args(args)
+        .current_dir(root.join(day))
+        .status()
+        .map_err(|err| AocError::Day {
+            day: day.to_string(),
+            message: err.to_string(),
+        })?;
+
+    if !status.success() {
+        return Err(AocError::Day {
+            day: day.to_string(),
+            message: status.to_string(),
+        });
+    }
+    Ok(())
+}
+
+fn get_bench_options(args: &[String]) -> Result<BenchOptions, AocError> {
+    let mut options = BenchOptions {
+        days: Vec::new(),
+        baseline: None,
+        save: false,
+        threshold: DEFAULT_THRESHOLD,
+    };
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--save" => options.save = true,
+            "--baseline" => options.baseline = Some(args.next().ok_or(AocError::Usage)?.clone()),
+            "--threshold" => {
+                options.threshold = args
+                    .next()
+                    .and_then(|threshold| threshold.parse::<f64>().ok())
+                    .ok_or(AocError::Usage)?
+            }
+            day => options.days.extend(get_day_args(&[day.to_string()])?),
+        }
+    }
+
+    Ok(options)
+}
+
+fn bench(root: &Path, options: &BenchOptions) -> Result<(), AocError> {
+    let days = if options.days.is_empty() {
+        get_days(root)?
+    } else {
+        options.days.clone()
+    };
+    let path = match &options.baseline {
+        Some(path) => PathBuf::from(path),
+        None => root.join(BASELINE_FILE),
+    };
+    let baseline = read_baseline(&path)?;
+
+    let mut timings: Vec<Timing> = Vec::new();
+    for day in days.iter() {
+        eprintln!("running {}", day);
+        timings.extend(get_timings(day, &run_day(root, day, &["--bench"])?)?);
+    }
+
+    print!("{}", format_table(&timings, &baseline, options.threshold));
+
+    if options.save {
+        std::fs::write(&path, format_baseline(&timings)).map_err(|err| AocError::Io {
+            path: path.display().to_string(),
+            message: err.to_string(),
+        })?;
+        println!("saved baseline to {}", path.display());
+    }
+    Ok(())
+}
+
+// Reads the "step<TAB>nanoseconds<TAB>runs" lines of a day started with --bench.
+fn get_timings(day: &str, output: &str) -> Result<Vec<Timing>, AocError> {
+    output
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let mut fields = line.split('\t');
+            match (fields.next(), fields.next().map(str::parse::<u128>)) {
+                (Some(step), Some(Ok(nanos))) => Ok(Timing {
+                    day: day.to_string(),
+                    step: step.to_string(),
+                    nanos,
+                }),
+                _ => Err(AocError::Day {
+                    day: day.to_string(),
+                    message: format!("unexpected bench output '{}'", line),
+                }),
+            }
+        })
+        .collect()
+}
+
+// A missing baseline file is the same as an empty one.
+fn read_baseline(path: &Path) -> Result<HashMap<(String, String), u128>, AocError> {
+    match std::fs::read_to_string(path) {
+        Ok(text) => parse_baseline(&text),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(HashMap::new()),
+        Err(err) => Err(AocError::Io {
+            path: path.display().to_string(),
+            message: err.to_string(),
+        }),
+    }
+}
+
+// day<TAB>step<TAB>nanoseconds
+fn parse_baseline(text: &str) -> Result<HashMap<(String, String), u128>, AocError> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            let fields: Vec<&str> = line.split('\t').collect();
+            match fields[..] {
+                [day, step, nanos] => nanos
+                    .parse::<u128>()
+                    .map(|nanos| ((day.to_string(), step.to_string()), nanos))
+                    .map_err(|_| AocError::Baseline {
+                        line: i + 1,
+                        text: line.to_string(),
+                    }),
+                _ => Err(AocError::Baseline {
+                    line: i + 1,
+                    text: line.to_string(),
+                }),
+            }
+        })
+        .collect()
+}
+
+fn format_baseline(timings: &[Timing]) -> String {
+    timings
+        .iter()
+        .map(|timing| format!("{}\t{}\t{}\n", timing.day, timing.step, timing.nanos))
+        .collect()
+}
+
+fn get_change(nanos: u128, baseline: Option<u128>, threshold: f64) -> Change {
+    match baseline {
+        None | Some(0) => Change::New,
+        Some(baseline) => {
+            let percent = (nanos as f64 - baseline as f64) / baseline as f64 * 100.0;
+            if percent > threshold {
+                Change::Slower(percent)
+            } else if percent < -threshold {
+                Change::Faster(percent)
+            } else {
+                Change::Same(percent)
+            }
+        }
+    }
+}
+
+// Three significant digits in the largest unit that keeps the number >= 1.
+fn format_duration(nanos: u128) -> String {
+    let units = [(1_000_000_000.0, "s"), (1_000_000.0, "ms"), (1_000.0, "µs")];
+    let nanos = nanos as f64;
+
+    for (size, unit) in units {
+        if nanos >= size {
+            let value = nanos / size;
+            return match value {
+                v if v >= 100.0 => format!("{:.0} {}", value, unit),
+                v if v >= 10.0 => format!("{:.1} {}", value, unit),
+                _ => format!("{:.2} {}", value, unit),
+            };
+        }
+    }
+    format!("{} ns", nanos)
+}
+
+fn format_table(
+    timings: &[Timing],
+    baseline: &HashMap<(String, String), u128>,
+    threshold: f64,
+) -> String {
+    let mut rows: Vec<[String; 5]> = vec![[
+        "day".to_string(),
+        "step".to_string(),
+        "time".to_string(),
+        "baseline".to_string(),
+        "change".to_string(),
+    ]];
+    let mut regressions = 0;
+
+    for timing in timings {
+        let before = baseline
+            .get(&(timing.day.clone(), timing.step.clone()))
+            .copied();
+        let change = match get_change(timing.nanos, before, threshold) {
+            Change::New => "new".to_string(),
+            Change::Same(percent) => format!("{:+.1}%", percent),
+            Change::Faster(percent) => format!("{:+.1}% faster", percent),
+            Change::Slower(percent) => {
+                regressions += 1;
+                format!("{:+.1}% SLOWER", percent)
+            }
+        };
+        rows.push([
+            timing.day.clone(),
+            timing.step.clone(),
+            format_duration(timing.nanos),
+            before.map_or("-".to_string(), format_duration),
+            change,
+        ]);
+    }
+
+    let mut widths = [0; 5];
+    for row in rows.iter() {
+        for (width, cell) in widths.iter_mut().zip(row.iter()) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let mut table = String::new();
+    for row in rows.iter() {
+        let cells: Vec<String> = row
+            .iter()
+            .zip(widths.iter())
+            .enumerate()
+            .map(|(i, (cell, width))| match i {
+                0 | 1 => format!("{:<width$}", cell, width = width),
+                _ => format!("{:>width$}", cell, width = width),
+            })
+            .collect();
+        table += cells.join("  ").trim_end();
+        table += "\n";
+    }
+    table += &format!("{} regression(s) over {}%\n", regressions, threshold);
+    table
+}
+
+fn verify(root: &Path, days: &[String]) -> Result<(), AocError> {
+    let path = root.join(ANSWERS_FILE);
+    let text = std::fs::read_to_string(&path).map_err(|err| AocError::Io {
+        path: path.display().to_string(),
+        message: err.to_string(),
+    })?;
+    let answers = parse_answers(&text)?;
+
+    let mut selected: Vec<(&String, &BTreeMap<String, String>)> = answers
+        .iter()
+        .filter(|(day, _)| days.is_empty() || days.contains(day))
+        .collect();
+    selected.sort_by_key(|(day, _)| get_day_number(day));
+
+    let mut checks: Vec<Check> = Vec::new();
+    for (day, parts) in selected.into_iter().filter(|(_, parts)| !parts.is_empty()) {
+        eprintln!("running {}", day);
+        let wanted: Vec<String> = parts.keys().cloned().collect();
+        let mut actual = run_parts(root, day, &wanted)?;
+        checks.extend(parts.iter().map(|(part, expected)| Check {
+            day: day.clone(),
+            part: part.clone(),
+            expected: expected.clone(),
+            actual: actual.remove(part),
+        }));
+    }
+
+    print!("{}", format_checks(&checks));
+
+    match checks.iter().filter(|check| !is_correct(check)).count() {
+        0 => Ok(()),
+        failed => Err(AocError::Verify { failed }),
+    }
+}
+
+// [dayN] tables of partN = answer, answers can be numbers or strings.
+fn parse_answers(text: &str) -> Result<Answers, AocError> {
+    let table: toml::Table = text
+        .parse()
+        .map_err(|err: toml::de::Error| AocError::Answers(err.message().to_string()))?;
+
+    table
+        .iter()
+        .map(|(day, parts)| {
+            let parts = parts
+                .as_table()
+                .ok_or_else(|| AocError::Answers(format!("{} is not a table", day)))?;
+            let parts = parts
+                .iter()
+                .map(|(part, answer)| match answer {
+                    toml::Value::String(answer) => Ok((part.clone(), answer.clone())),
+                    toml::Value::Integer(answer) => Ok((part.clone(), answer.to_string())),
+                    _ => Err(AocError::Answers(format!(
+                        "{}.{} is not a number or a string",
+                        day, part
+                    ))),
+                })
+                .collect::<Result<BTreeMap<String, String>, AocError>>()?;
+            Ok((day.clone(), parts))
+        })
+        .collect()
+}
+
+// "Part 2: 1124" -> ("part2", "1124")
+fn get_part_answer(line: &str) -> Option<(String, String)> {
+    let (part, answer) = line.strip_prefix("Part ")?.split_once(": ")?;
+    part.parse::<u32>().ok()?;
+    Some((format!("part{}", part), answer.trim().to_string()))
+}
+
+// Runs a day and collects its "Part N: answer" lines. The day is stopped as soon as all wanted
+// parts are printed, so a slow part that is not in the registry does not hold up the rest.
+fn run_parts(
+    root: &Path,
+    day: &str,
+    wanted: &[String],
+) -> Result<BTreeMap<String, String>, AocError> {
+    let mut child = Command::new(build_day(root, day)?)
+        .current_dir(root.join(day))
+        .stdout(Stdio::piped())
+        .spawn()
+        .map_err(|err| AocError::Day {
+            day: day.to_string(),
+            message: err.to_string(),
+        })?;
+
+    let mut answers: BTreeMap<String, String> = BTreeMap::new();
+    if let Some(stdout) = child.stdout.take() {
+        for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+            if let Some((part, answer)) = get_part_answer(&line) {
+                answers.insert(part, answer);
+            }
+            if wanted.iter().all(|part| answers.contains_key(part)) {
+                break;
+            }
+        }
+    }
+    let _ = child.kill();
+    let _ = child.wait();
+
+    Ok(answers)
+}
+
+fn is_correct(check: &Check) -> bool {
+    check.actual.as_ref() == Some(&check.expected)
+}
+
+fn format_checks(checks: &[Check]) -> String {
+    let mut table = String::new();
+    for check in checks {
+        let verdict = match &check.actual {
+            _ if is_correct(check) => "ok".to_string(),
+            Some(actual) => format!("WRONG, got {}", actual),
+            None => "MISSING".to_string(),
+        };
+        table += &format!(
+            "{:<6} {:<6} {:>16}  {}\n",
+            check.day, check.part, check.expected, verdict
+        );
+    }
+    table
+}
+
+fn get_io_error(path: &Path) -> impl Fn(std::io::Error) -> AocError + '_ {
+    move |err| AocError::Io {
+        path: path.display().to_string(),
+        message: err.to_string(),
+    }
+}
+
+// <day> [--readme file], returns the day and the text of the readme.
+fn get_new_options(args: &[String]) -> Result<(String, Option<String>), AocError> {
+    let (day, rest) = args.split_first().ok_or(AocError::Usage)?;
+    let day = get_day_args(std::slice::from_ref(day))?.remove(0);
+
+    match rest {
+        [] => Ok((day, None)),
+        [flag, path] if flag == "--readme" => {
+            let readme = std::fs::read_to_string(path).map_err(get_io_error(Path::new(path)))?;
+            Ok((day, Some(readme)))
+        }
+        _ => Err(AocError::Usage),
+    }
+}
+
+// Creates dayN from the template: package name, empty input, readme (the puzzle text if we
+// have it) and an entry in answers.toml. The examples of the puzzle text become tests on build.
+fn new_day(root: &Path, day: &str, readme: Option<&str>) -> Result<PathBuf, AocError> {
+    let dir = root.join(day);
+    if dir.exists() {
+        return Err(AocError::Day {
+            day: day.to_string(),
+            message: "already exists".to_string(),
+        });
+    }
+    copy_template(&root.join(TEMPLATE_DIR), &dir)?;
+
+    let manifest = dir.join("Cargo.toml");
+    let text = std::fs::read_to_string(&manifest).map_err(get_io_error(&manifest))?;
+    let text = text.replace("name = \"template\"", &format!("name = \"{}\"", day));
+    std::fs::write(&manifest, text).map_err(get_io_error(&manifest))?;
+
+    let readme = match readme {
+        Some(readme) => readme.to_string(),
+        None => format!(
+            "--- Day {} ---\n\nPaste the puzzle text here as it is copied from the site, its examples are\nturned into tests.\n",
+            get_day_number(day).unwrap_or_default()
+        ),
+    };
+    let path = dir.join("Readme.md");
+    std::fs::write(&path, &readme).map_err(get_io_error(&path))?;
+
+    let path = dir.join("input.txt");
+    std::fs::write(&path, "").map_err(get_io_error(&path))?;
+
+    if !common::readme::get_examples(&readme).is_empty() {
+        let path = dir.join("src").join("main.rs");
+        let text = std::fs::read_to_string(&path).map_err(get_io_error(&path))?;
+        let text = text.replace(
+            "mod tests {\n",
+            "mod tests {\n    // the first example of the readme, remove the allow once a test uses it\n    #[allow(dead_code)]\n    fn get_test_input() -> Vec<String> {\n        get_example_input(1)\n    }\n\n",
+        );
+        std::fs::write(&path, text).map_err(get_io_error(&path))?;
+    }
+
+    let path = root.join(ANSWERS_FILE);
+    let answers = std::fs::read_to_string(&path).unwrap_or_default();
+    if !parse_answers(&answers)?.contains_key(day) {
+        let separator = if answers.is_empty() || answers.ends_with("\n\n") {
+            ""
+        } else if answers.ends_with('\n') {
+            "\n"
+        } else {
+            "\n\n"
+        };
+        std::fs::write(&path, format!("{}{}[{}]\n", answers, separator, day))
+            .map_err(get_io_error(&path))?;
+    }
+
+    Ok(dir)
+}
+
+// <day> [--year year]
+fn get_fetch_options(args: &[String]) -> Result<(String, Option<u32>), AocError> {
+    let (day, rest) = args.split_first().ok_or(AocError::Usage)?;
+    let day = get_day_args(std::slice::from_ref(day))?.remove(0);
+
+    match rest {
+        [] => Ok((day, None)),
+        [flag, year] if flag == "--year" => {
+            Ok((day, Some(year.parse().map_err(|_| AocError::Usage)?)))
+        }
+        _ => Err(AocError::Usage),
+    }
+}
+
+// Puts the (cached) input into dayN/input.txt, which is only overwritten while it is empty.
+fn fetch_day(root: &Path, day: &str, fetcher: &Fetcher) -> Result<Vec<PathBuf>, AocError> {
+    let number = get_day_number(day).ok_or(AocError::Usage)?;
+    let dir = root.join(day);
+    if !dir.is_dir() {
+        return Err(AocError::Day {
+            day: day.to_string(),
+            message: "does not exist, create it with aoc new".to_string(),
+        });
+    }
+
+    let input = fetcher.fetch_input(number).map_err(AocError::Fetch)?;
+    let paths: Vec<PathBuf> = get_input_names(&dir)
+        .iter()
+        .map(|name| dir.join(name))
+        .collect();
+    for path in paths.iter() {
+        match std::fs::read_to_string(path) {
+            Ok(text) if !text.is_empty() && text != input => {
+                return Err(AocError::Day {
+                    day: day.to_string(),
+                    message: format!(
+                        "{} already holds another input",
+                        path.file_name().unwrap_or_default().to_string_lossy()
+                    ),
+                })
+            }
+            _ => (),
+        }
+    }
+    for path in paths.iter() {
+        std::fs::write(path, &input).map_err(get_io_error(path))?;
+    }
+
+    Ok(paths)
+}
+
+// The files the day reads, from its InputFiles::new(env!("CARGO_MANIFEST_DIR"), part1, part2)
+// with string literals or &str constants. A day that does not say reads input.txt like the
+// template.
+fn get_input_names(dir: &Path) -> Vec<String> {
+    let source = std::fs::read_to_string(dir.join("src").join("main.rs")).unwrap_or_default();
+    let call = "InputFiles::new(";
+    let args = match source.find(call) {
+        Some(start) => &source[start + call.len()..],
+        None => return vec!["input.txt".to_string()],
+    };
+
+    // the arguments up to the closing parenthesis, split at the commas outside of env!(...)
+    let mut values: Vec<&str> = Vec::new();
+    let (mut depth, mut start) = (0, 0);
+    for (i, c) in args.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' if depth > 0 => depth -= 1,
+            ',' | ')' if depth == 0 => {
+                values.push(args[start..i].trim());
+                start = i + 1;
+                if c == ')' {
+                    break;
+                }
+            }
+            _ => (),
+        }
+    }
+
+    let mut names: Vec<String> = Vec::new();
+    for value in values.iter().skip(1) {
+        let name = match value.strip_prefix('"') {
+            Some(literal) => literal.strip_suffix('"').map(str::to_string),
+            None => get_str_const(&source, value),
+        };
+        names.extend(name.filter(|name| !names.contains(name)));
+    }
+    if names.is_empty() {
+        names.push("input.txt".to_string());
+    }
+    names
+}
+
+// const NAME: &str = "value";
+fn get_str_const(source: &str, name: &str) -> Option<String> {
+    let prefix = format!("const {}: &str = \"", name);
+    let start = source.find(&prefix)? + prefix.len();
+    let end = source[start..].find('"')?;
+
+    Some(source[start..start + end].to_string())
+}
+
+// <day> <part> [--answer answer] [--year year]
+fn get_submit_options(args: &[String]) -> Result<SubmitOptions, AocError> {
+    let [day, part, rest @ ..] = args else {
+        return Err(AocError::Usage);
+    };
+    let mut options = SubmitOptions {
+        day: get_day_args(std::slice::from_ref(day))?.remove(0),
+        part: match part.strip_prefix("part").unwrap_or(part) {
+            "1" => 1,
+            "2" => 2,
+            _ => return Err(AocError::Usage),
+        },
+        answer: None,
+        year: None,
+    };
+
+    let mut rest = rest.iter();
+    while let Some(arg) = rest.next() {
+        let value = rest.next().ok_or(AocError::Usage)?;
+        match arg.as_str() {
+            "--answer" => options.answer = Some(value.clone()),
+            "--year" => options.year = Some(value.parse().map_err(|_| AocError::Usage)?),
+            _ => return Err(AocError::Usage),
+        }
+    }
+    Ok(options)
+}
+
+// Sends what the day prints for the part, or the given answer. Only a right answer succeeds.
+fn submit(root: &Path, options: &SubmitOptions, fetcher: &Fetcher) -> Result<(), AocError> {
+    let day = &options.day;
+    let part = format!("part{}", options.part);
+    let answer = match &options.answer {
+        Some(answer) => answer.clone(),
+        None => {
+            eprintln!("running {}", day);
+            run_parts(root, day, std::slice::from_ref(&part))?
+                .remove(&part)
+                .ok_or_else(|| AocError::Day {
+                    day: day.clone(),
+                    message: format!("printed no answer for {}", part),
+                })?
+        }
+    };
+
+    println!("{} {}: {}", day, part, answer);
+    let number = get_day_number(day).ok_or(AocError::Usage)?;
+    match fetcher
+        .submit(number, options.part, &answer)
+        .map_err(AocError::Fetch)?
+    {
+        Verdict::Right => {
+            println!("right answer, add it to {}", ANSWERS_FILE);
+            Ok(())
+        }
+        verdict => Err(AocError::Submit(verdict)),
+    }
+}
+
+// Copies the template without build output.
+fn copy_template(from: &Path, to: &Path) -> Result<(), AocError> {
+    std::fs::create_dir_all(to).map_err(get_io_error(to))?;
+
+    for entry in std::fs::read_dir(from).map_err(get_io_error(from))? {
+        let entry = entry.map_err(get_io_error(from))?;
+        let name = entry.file_name();
+        if name == "target" || name == "Cargo.lock" {
+            continue;
+        }
+
+        let path = entry.path();
+        if path.is_dir() {
+            copy_template(&path, &to.join(&name))?;
+        } else {
+            std::fs::copy(&path, to.join(&name)).map_err(get_io_error(&path))?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    fn timing(day: &str, step: &str, nanos: u128) -> super::Timing {
+        super::Timing {
+            day: day.to_string(),
+            step: step.to_string(),
+            nanos,
+        }
+    }
+
+    #[test]
+    fn test_get_timings() {
+        assert_eq!(
+            super::get_timings("day4", "parse\t1200\t1000\npart1\t35000\t5\n"),
+            Ok(vec![
+                timing("day4", "parse", 1200),
+                timing("day4", "part1", 35000)
+            ])
+        );
+        assert!(super::get_timings("day4", "Part 1: 13\n").is_err());
+    }
+
+    #[test]
+    fn test_baseline() {
+        let timings = vec![timing("day1", "part1", 5), timing("day10", "part2", 70)];
+        let baseline = super::parse_baseline(&super::format_baseline(&timings)).unwrap();
+
+        assert_eq!(baseline.len(), 2);
+        assert_eq!(baseline[&("day10".to_string(), "part2".to_string())], 70);
+        assert_eq!(
+            super::parse_baseline("day1\tpart1\t5\n\nday1\tpart2\n"),
+            Err(super::AocError::Baseline {
+                line: 3,
+                text: "day1\tpart2".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_get_change() {
+        assert_eq!(super::get_change(100, None, 10.0), super::Change::New);
+        assert_eq!(
+            super::get_change(105, Some(100), 10.0),
+            super::Change::Same(5.0)
+        );
+        assert_eq!(
+            super::get_change(150, Some(100), 10.0),
+            super::Change::Slower(50.0)
+        );
+        assert_eq!(
+            super::get_change(50, Some(100), 10.0),
+            super::Change::Faster(-50.0)
+        );
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(super::format_duration(999), "999 ns");
+        assert_eq!(super::format_duration(1_234), "1.23 µs");
+        assert_eq!(super::format_duration(45_600_000), "45.6 ms");
+        assert_eq!(super::format_duration(123_000_000_000), "123 s");
+    }
+
+    #[test]
+    fn test_format_table() {
+        let timings = vec![timing("day1", "part1", 2_000), timing("day1", "part2", 500)];
+        let mut baseline = HashMap::new();
+        baseline.insert(("day1".to_string(), "part1".to_string()), 1_000);
+
+        assert_eq!(
+            super::format_table(&timings, &baseline, 10.0),
+            [
+                "day   step      time  baseline          change",
+                "day1  part1  2.00 µs   1.00 µs  +100.0% SLOWER",
+                "day1  part2   500 ns         -             new",
+                "1 regression(s) over 10%",
+                "",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn test_get_bench_options() {
+        let args: Vec<String> = ["4", "day10", "--save", "--threshold", "5"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(
+            super::get_bench_options(&args),
+            Ok(super::BenchOptions {
+                days: vec!["day4".to_string(), "day10".to_string()],
+                baseline: None,
+                save: true,
+                threshold: 5.0,
+            })
+        );
+        assert_eq!(
+            super::get_bench_options(&["--threshold".to_string()]),
+            Err(super::AocError::Usage)
+        );
+    }
+
+    #[test]
+    fn test_parse_answers() {
+        let answers = super::parse_answers("[day4]\npart1 = 13\npart2 = \"30\"\n").unwrap();
+        assert_eq!(answers["day4"]["part1"], "13");
+        assert_eq!(answers["day4"]["part2"], "30");
+
+        assert!(super::parse_answers("day4 = 13").is_err());
+        assert!(super::parse_answers("[day4]\npart1 = 1.5").is_err());
+        assert!(super::parse_answers("[day4").is_err());
+    }
+
+    #[test]
+    fn test_get_part_answer() {
+        assert_eq!(
+            super::get_part_answer("Part 2: 1124"),
+            Some(("part2".to_string(), "1124".to_string()))
+        );
+        assert_eq!(super::get_part_answer("start = 1, range = 2"), None);
+        assert_eq!(super::get_part_answer("Part x: 1"), None);
+    }
+
+    #[test]
+    fn test_format_checks() {
+        let check = |part: &str, actual: Option<&str>| super::Check {
+            day: "day4".to_string(),
+            part: part.to_string(),
+            expected: "13".to_string(),
+            actual: actual.map(String::from),
+        };
+        let checks = vec![
+            check("part1", Some("13")),
+            check("part2", Some("12")),
+            check("part3", None),
+        ];
+
+        assert_eq!(
+            super::format_checks(&checks),
+            [
+                "day4   part1                13  ok",
+                "day4   part2                13  WRONG, got 12",
+                "day4   part3                13  MISSING",
+                "",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn test_get_day_args() {
+        assert_eq!(
+            super::get_day_args(&["4".to_string(), "day10".to_string()]),
+            Ok(vec!["day4".to_string(), "day10".to_string()])
+        );
+        assert_eq!(
+            super::get_day_args(&["dayx".to_string()]),
+            Err(super::AocError::Usage)
+        );
+    }
+
+    // Runs the day on its real input and compares with answers.toml.
+    fn check_answer(day: &str, part: &str, expected: &str) {
+        let answers = super::run_parts(&super::get_root(), day, &[part.to_string()]).unwrap();
+        assert_eq!(answers.get(part).map(String::as_str), Some(expected));
+    }
+
+    include!(concat!(env!("OUT_DIR"), "/answer_tests.rs"));
+
+    #[test]
+    fn test_new_day() {
+        let root = std::env::temp_dir().join(format!("aoc-new-day-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        super::copy_template(
+            &super::get_root().join(super::TEMPLATE_DIR),
+            &root.join(super::TEMPLATE_DIR),
+        )
+        .unwrap();
+        std::fs::write(root.join(super::ANSWERS_FILE), "[day1]\npart1 = 1\n").unwrap();
+
+        let readme =
+            "--- Day 14: Test ---\nFor example:\n\nO.#\nIn this example, that is 3.\nWhat is it?\n";
+        let dir = super::new_day(&root, "day14", Some(readme)).unwrap();
+        let read = |path: &str| std::fs::read_to_string(dir.join(path)).unwrap();
+
+        assert!(read("Cargo.toml").contains("name = \"day14\""));
+        assert_eq!(read("Readme.md"), readme);
+        assert_eq!(read("input.txt"), "");
+        assert!(read("src/main.rs").contains("get_example_input(1)"));
+        assert!(!dir.join("target").exists());
+        assert_eq!(
+            std::fs::read_to_string(root.join(super::ANSWERS_FILE)).unwrap(),
+            "[day1]\npart1 = 1\n\n[day14]\n"
+        );
+
+        // without a puzzle text there is nothing to seed the tests with
+        let dir = super::new_day(&root, "day15", None).unwrap();
+        assert!(read("Readme.md").starts_with("--- Day 14"));
+        assert!(std::fs::read_to_string(dir.join("Readme.md"))
+            .unwrap()
+            .starts_with("--- Day 15 ---"));
+        assert!(!std::fs::read_to_string(dir.join("src/main.rs"))
+            .unwrap()
+            .contains("get_test_input"));
+
+        assert!(super::new_day(&root, "day14", None).is_err());
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_fetch_day() {
+        let root = std::env::temp_dir().join(format!("aoc-fetch-day-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("day14")).unwrap();
+        std::fs::write(root.join("day14").join("input.txt"), "").unwrap();
+
+        // only the cache is used, there is no session and the url goes nowhere
+        let fetcher = super::Fetcher {
+            url: "http://127.0.0.1:9".to_string(),
+            year: 2023,
+            session: None,
+            cache: root.join("cache"),
+        };
+        std::fs::create_dir_all(root.join("cache").join("2023")).unwrap();
+        std::fs::write(fetcher.get_path(14), "O.#\n").unwrap();
+
+        let paths = super::fetch_day(&root, "day14", &fetcher).unwrap();
+        assert_eq!(paths, vec![root.join("day14").join("input.txt")]);
+        assert_eq!(std::fs::read_to_string(&paths[0]).unwrap(), "O.#\n");
+        assert!(super::fetch_day(&root, "day14", &fetcher).is_ok());
+
+        std::fs::write(&paths[0], "#.O\n").unwrap();
+        assert!(super::fetch_day(&root, "day14", &fetcher).is_err());
+        assert_eq!(
+            super::fetch_day(&root, "day15", &fetcher),
+            Err(super::AocError::Day {
+                day: "day15".to_string(),
+                message: "does not exist, create it with aoc new".to_string()
+            })
+        );
+
+        // a day that reads one file per part gets both
+        std::fs::create_dir_all(root.join("day17").join("src")).unwrap();
+        std::fs::write(
+            root.join("day17").join("src").join("main.rs"),
+            "let files = InputFiles::new(env!(\"CARGO_MANIFEST_DIR\"), \"input1.txt\", \"input2.txt\");",
+        )
+        .unwrap();
+        std::fs::write(fetcher.get_path(17), "O.#\n").unwrap();
+        let paths = super::fetch_day(&root, "day17", &fetcher).unwrap();
+        assert_eq!(paths.len(), 2);
+        for path in paths.iter() {
+            assert_eq!(std::fs::read_to_string(path).unwrap(), "O.#\n");
+        }
+
+        std::fs::create_dir_all(root.join("day16")).unwrap();
+        assert_eq!(
+            super::fetch_day(&root, "day16", &fetcher),
+            Err(super::AocError::Fetch(super::FetchError::NoSession))
+        );
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_get_input_names() {
+        let names = |day: &str| super::get_input_names(&super::get_root().join(day));
+
+        assert_eq!(names("day6"), vec!["input1.txt", "input2.txt"]);
+        assert_eq!(names("day2"), vec!["input.txt"]);
+        assert_eq!(names("day13"), vec!["input.txt"]);
+        assert_eq!(names("_template"), vec!["input.txt"]);
+        assert_eq!(names("day99"), vec!["input.txt"]);
+    }
+
+    #[test]
+    fn test_get_run_options() {
+        let args = |args: &[&str]| -> Vec<String> { args.iter().map(|s| s.to_string()).collect() };
+        assert_eq!(
+            super::get_run_options(&args(&["7", "a.txt", "-"])),
+            Ok(("day7".to_string(), args(&["a.txt", "-"])))
+        );
+        assert_eq!(
+            super::get_run_options(&args(&["day7"])),
+            Ok(("day7".to_string(), Vec::new()))
+        );
+        assert!(super::get_run_options(&args(&[])).is_err());
+
+        assert_eq!(
+            super::get_input_args(
+                &args(&["a.txt", "-", "/in/b.txt"]),
+                std::path::Path::new("/home")
+            ),
+            args(&["--input", "/home/a.txt", "-", "/in/b.txt"])
+        );
+        assert!(super::get_input_args(&[], std::path::Path::new("/home")).is_empty());
+    }
+
+    #[test]
+    fn test_get_fetch_options() {
+        let args = |args: &[&str]| -> Vec<String> { args.iter().map(|s| s.to_string()).collect() };
+        assert_eq!(
+            super::get_fetch_options(&args(&["14"])),
+            Ok(("day14".to_string(), None))
+        );
+        assert_eq!(
+            super::get_fetch_options(&args(&["day3", "--year", "2022"])),
+            Ok(("day3".to_string(), Some(2022)))
+        );
+        assert!(super::get_fetch_options(&args(&["3", "--year"])).is_err());
+        assert!(super::get_fetch_options(&args(&[])).is_err());
+    }
+
+    #[test]
+    fn test_get_submit_options() {
+        let args = |args: &[&str]| -> Vec<String> { args.iter().map(|s| s.to_string()).collect() };
+        assert_eq!(
+            super::get_submit_options(&args(&["7", "2"])),
+            Ok(super::SubmitOptions {
+                day: "day7".to_string(),
+                part: 2,
+                answer: None,
+                year: None
+            })
+        );
+        assert_eq!(
+            super::get_submit_options(&args(&[
+                "day7", "part1", "--answer", "12", "--year", "2022"
+            ])),
+            Ok(super::SubmitOptions {
+                day: "day7".to_string(),
+                part: 1,
+                answer: Some("12".to_string()),
+                year: Some(2022)
+            })
+        );
+        assert!(super::get_submit_options(&args(&["7", "3"])).is_err());
+        assert!(super::get_submit_options(&args(&["7"])).is_err());
+        assert!(super::get_submit_options(&args(&["7", "1", "--answer"])).is_err());
+    }
+
+    #[test]
+    fn test_get_days() {
+        let days = super::get_days(&super::get_root()).unwrap();
+        assert_eq!(days.first().map(String::as_str), Some("day1"));
+        assert!(days.iter().all(|day| day.starts_with("day")));
+        assert!(days
+            .windows(2)
+            .all(|w| { w[0][3..].parse::<u32>().unwrap() < w[1][3..].parse::<u32>().unwrap() }));
+    }
+}
@@ -0,0 +1,64 @@
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+// Every step is run at least this many times, and then until the time budget is used up.
+const MIN_RUNS: usize = 5;
+const MAX_RUNS: usize = 1000;
+const BUDGET: Duration = Duration::from_secs(1);
+
+// true if the day was started with --bench
+pub fn requested() -> bool {
+    std::env::args().skip(1).any(|arg| arg == "--bench")
+}
+
+#[derive(Debug)]
+pub struct Timing {
+    pub name: String,
+    pub runs: usize,
+    pub median: Duration,
+}
+
+#[derive(Debug, Default)]
+pub struct Bench {
+    pub timings: Vec<Timing>,
+}
+
+impl Bench {
+    pub fn new() -> Bench {
+        Bench::default()
+    }
+
+    pub fn run<T>(&mut self, name: &str, mut f: impl FnMut() -> T) -> &mut Bench {
+        let start = Instant::now();
+        let mut samples: Vec<Duration> = Vec::new();
+
+        while samples.len() < MIN_RUNS || (samples.len() < MAX_RUNS && start.elapsed() < BUDGET) {
+            let run = Instant::now();
+            black_box(f());
+            samples.push(run.elapsed());
+        }
+
+        samples.sort();
+        self.timings.push(Timing {
+            name: name.to_string(),
+            runs: samples.len(),
+            median: samples[samples.len() / 2],
+        });
+        self
+    }
+
+    // One "name<TAB>nanoseconds<TAB>runs" line per step, read back by `aoc bench`.
+    pub fn report(&self) -> String {
+        self.timings
+            .iter()
+            .map(|timing| {
+                format!(
+                    "{}\t{}\t{}\n",
+                    timing.name,
+                    timing.median.as_nanos(),
+                    timing.runs
+                )
+            })
+            .collect()
+    }
+}
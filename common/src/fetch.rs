@@ -0,0 +1,270 @@
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+pub const DEFAULT_URL: &str = "https://adventofcode.com";
+pub const DEFAULT_YEAR: u32 = 2023;
+
+#[derive(Debug, PartialEq)]
+pub enum FetchError {
+    NoSession,
+    NotAvailable { year: u32, day: u32 },
+    Http { status: u16, message: String },
+    Network(String),
+    Io { path: String, message: String },
+    // the submission history already shows how the answer would end
+    Refused(String),
+}
+
+impl Display for FetchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FetchError::NoSession => write!(
+                f,
+                "no session cookie, set AOC_SESSION or put it in ~/.config/aoc/session"
+            ),
+            FetchError::NotAvailable { year, day } => {
+                write!(f, "the input of {} day {} is not available yet", year, day)
+            }
+            FetchError::Http { status, message } => {
+                write!(f, "the server answered {}: {}", status, message)
+            }
+            FetchError::Network(message) => write!(f, "{}", message),
+            FetchError::Io { path, message } => write!(f, "{}: {}", path, message),
+            FetchError::Refused(reason) => write!(f, "not sent, {}", reason),
+        }
+    }
+}
+
+// Downloads puzzle inputs once and keeps them in cache/<year>/day<N>.txt.
+#[derive(Debug, Clone)]
+pub struct Fetcher {
+    pub url: String,
+    pub year: u32,
+    pub session: Option<String>,
+    pub cache: PathBuf,
+}
+
+impl Fetcher {
+    // Everything from the environment: AOC_SESSION (or ~/.config/aoc/session), AOC_YEAR,
+    // AOC_CACHE (or ~/.cache/aoc) and AOC_URL to talk to another server.
+    pub fn from_env() -> Fetcher {
+        let home = std::env::var("HOME").map(PathBuf::from).unwrap_or_default();
+
+        Fetcher {
+            url: std::env::var("AOC_URL").unwrap_or_else(|_| DEFAULT_URL.to_string()),
+            year: std::env::var("AOC_YEAR")
+                .ok()
+                .and_then(|year| year.parse().ok())
+                .unwrap_or(DEFAULT_YEAR),
+            session: get_session(&home),
+            cache: std::env::var("AOC_CACHE")
+                .map(PathBuf::from)
+                .unwrap_or_else(|_| home.join(".cache").join("aoc")),
+        }
+    }
+
+    pub fn get_path(&self, day: u32) -> PathBuf {
+        self.cache
+            .join(self.year.to_string())
+            .join(format!("day{}.txt", day))
+    }
+
+    // The cached input if there is one, otherwise it is downloaded and cached.
+    pub fn fetch_input(&self, day: u32) -> Result<String, FetchError> {
+        let path = self.get_path(day);
+        if let Ok(input) = std::fs::read_to_string(&path) {
+            return Ok(input);
+        }
+
+        let session = self.session.as_ref().ok_or(FetchError::NoSession)?;
+        let input = self.download(day, session)?;
+        write_atomic(&path, &input)?;
+
+        Ok(input)
+    }
+
+    fn download(&self, day: u32, session: &str) -> Result<String, FetchError> {
+        let url = format!("{}/{}/day/{}/input", self.url, self.year, day);
+        let agent = ureq::AgentBuilder::new()
+            .timeout(Duration::from_secs(30))
+            .build();
+
+        match agent
+            .get(&url)
+            .set("Cookie", &format!("session={}", session))
+            .set("User-Agent", "aoc runner (common::fetch)")
+            .call()
+        {
+            Ok(response) => response
+                .into_string()
+                .map_err(|err| FetchError::Network(err.to_string())),
+            Err(ureq::Error::Status(404, _)) => Err(FetchError::NotAvailable {
+                year: self.year,
+                day,
+            }),
+            Err(ureq::Error::Status(status, response)) => Err(FetchError::Http {
+                status,
+                message: response
+                    .into_string()
+                    .unwrap_or_default()
+                    .trim()
+                    .to_string(),
+            }),
+            Err(err) => Err(FetchError::Network(err.to_string())),
+        }
+    }
+}
+
+fn get_session(home: &Path) -> Option<String> {
+    std::env::var("AOC_SESSION")
+        .ok()
+        .or_else(|| std::fs::read_to_string(home.join(".config").join("aoc").join("session")).ok())
+        .map(|session| session.trim().to_string())
+        .filter(|session| !session.is_empty())
+}
+
+// Written next to the target and renamed, so an interrupted download never looks cached.
+fn write_atomic(path: &Path, text: &str) -> Result<(), FetchError> {
+    let io_error = |err: std::io::Error| FetchError::Io {
+        path: path.display().to_string(),
+        message: err.to_string(),
+    };
+
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(io_error)?;
+    }
+    let partial = path.with_extension("part");
+    std::fs::write(&partial, text).map_err(io_error)?;
+    std::fs::rename(&partial, path).map_err(io_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+    use std::sync::{Arc, Mutex};
+
+    // (path, cookie) of every request the mock server got
+    type Requests = Arc<Mutex<Vec<(String, String)>>>;
+
+    // Serves every request with the given status and body until the test ends.
+    fn start_server(status: u16, body: &'static str) -> (String, Requests) {
+        let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", server.server_addr().to_ip().unwrap());
+        let requests: Requests = Arc::new(Mutex::new(Vec::new()));
+
+        let seen = requests.clone();
+        std::thread::spawn(move || {
+            for request in server.incoming_requests() {
+                let cookie = request
+                    .headers()
+                    .iter()
+                    .find(|header| header.field.equiv("Cookie"))
+                    .map(|header| header.value.to_string())
+                    .unwrap_or_default();
+                seen.lock()
+                    .unwrap()
+                    .push((request.url().to_string(), cookie));
+                let response = tiny_http::Response::from_string(body).with_status_code(status);
+                let _ = request.respond(response);
+            }
+        });
+
+        (url, requests)
+    }
+
+    fn get_cache(name: &str) -> PathBuf {
+        let cache = std::env::temp_dir().join(format!("aoc-fetch-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&cache);
+        cache
+    }
+
+    fn get_fetcher(url: &str, session: Option<&str>, cache: &Path) -> super::Fetcher {
+        super::Fetcher {
+            url: url.to_string(),
+            year: 2023,
+            session: session.map(String::from),
+            cache: cache.to_path_buf(),
+        }
+    }
+
+    #[test]
+    fn test_fetch_input_once() {
+        let (url, requests) = start_server(200, "1abc2\npqr3stu8vwx\n");
+        let cache = get_cache("once");
+        let fetcher = get_fetcher(&url, Some("secret"), &cache);
+
+        assert_eq!(
+            fetcher.fetch_input(1),
+            Ok("1abc2\npqr3stu8vwx\n".to_string())
+        );
+        assert_eq!(
+            fetcher.fetch_input(1),
+            Ok("1abc2\npqr3stu8vwx\n".to_string())
+        );
+
+        assert_eq!(
+            *requests.lock().unwrap(),
+            vec![(
+                "/2023/day/1/input".to_string(),
+                "session=secret".to_string()
+            )]
+        );
+        assert_eq!(
+            std::fs::read_to_string(cache.join("2023").join("day1.txt")).unwrap(),
+            "1abc2\npqr3stu8vwx\n"
+        );
+
+        // a cached input does not need a session
+        let fetcher = get_fetcher(&url, None, &cache);
+        assert!(fetcher.fetch_input(1).is_ok());
+        assert_eq!(requests.lock().unwrap().len(), 1);
+
+        std::fs::remove_dir_all(&cache).unwrap();
+    }
+
+    #[test]
+    fn test_fetch_input_errors() {
+        let cache = get_cache("errors");
+
+        let (url, requests) = start_server(200, "input");
+        let fetcher = get_fetcher(&url, None, &cache);
+        assert_eq!(fetcher.fetch_input(2), Err(super::FetchError::NoSession));
+        assert!(requests.lock().unwrap().is_empty());
+
+        let (url, _) = start_server(404, "Not Found");
+        let fetcher = get_fetcher(&url, Some("secret"), &cache);
+        assert_eq!(
+            fetcher.fetch_input(25),
+            Err(super::FetchError::NotAvailable {
+                year: 2023,
+                day: 25
+            })
+        );
+
+        let (url, _) = start_server(
+            400,
+            "Puzzle inputs differ by user.  Please log in to get your puzzle input.\n",
+        );
+        let fetcher = get_fetcher(&url, Some("expired"), &cache);
+        assert_eq!(
+            fetcher.fetch_input(2),
+            Err(super::FetchError::Http {
+                status: 400,
+                message: "Puzzle inputs differ by user.  Please log in to get your puzzle input."
+                    .to_string()
+            })
+        );
+
+        // failed downloads are not cached
+        assert!(!fetcher.get_path(2).exists());
+        assert!(!fetcher.get_path(25).exists());
+        let _ = std::fs::remove_dir_all(&cache);
+    }
+
+    #[test]
+    fn test_get_path() {
+        let fetcher = get_fetcher("http://localhost", None, &PathBuf::from("/cache"));
+        assert_eq!(fetcher.get_path(7), PathBuf::from("/cache/2023/day7.txt"));
+    }
+}
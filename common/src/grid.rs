@@ -0,0 +1,157 @@
+use crate::parse::{get_span_error, ParseError};
+use crate::Point;
+
+// A rectangle of cells, Point { x: 0, y: 0 } is the top left.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Grid<T> {
+    pub width: usize,
+    pub height: usize,
+    cells: Vec<T>, // row by row
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Grid<T> {
+        Grid {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    pub fn contains(&self, point: Point) -> bool {
+        (0..self.width as i64).contains(&point.x) && (0..self.height as i64).contains(&point.y)
+    }
+
+    pub fn get(&self, point: Point) -> Option<&T> {
+        self.get_index(point).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+        self.get_index(point).map(|i| &mut self.cells[i])
+    }
+
+    // false if the point is outside
+    pub fn set(&mut self, point: Point, value: T) -> bool {
+        match self.get_mut(point) {
+            Some(cell) => {
+                *cell = value;
+                true
+            }
+            None => false,
+        }
+    }
+
+    // every point in reading order
+    pub fn points(&self) -> impl Iterator<Item = Point> + '_ {
+        (0..self.height as i64)
+            .flat_map(move |y| (0..self.width as i64).map(move |x| Point { x, y }))
+    }
+
+    pub fn find(&self, mut predicate: impl FnMut(&T) -> bool) -> Option<Point> {
+        self.points()
+            .zip(self.cells.iter())
+            .find(|(_, cell)| predicate(cell))
+            .map(|(point, _)| point)
+    }
+
+    // The points up, right, down and left of point that are inside the grid.
+    pub fn get_neighbours(&self, point: Point) -> Vec<Point> {
+        [(0, -1), (1, 0), (0, 1), (-1, 0)]
+            .iter()
+            .map(|(dx, dy)| Point {
+                x: point.x + dx,
+                y: point.y + dy,
+            })
+            .filter(|next| self.contains(*next))
+            .collect()
+    }
+
+    fn get_index(&self, point: Point) -> Option<usize> {
+        match self.contains(point) {
+            true => Some(point.y as usize * self.width + point.x as usize),
+            false => None,
+        }
+    }
+}
+
+impl Grid<char> {
+    // One row per line, every row as wide as the first.
+    pub fn from_lines(lines: &[String]) -> Result<Grid<char>, ParseError> {
+        let width = lines.first().map_or(0, |line| line.chars().count());
+        let mut cells: Vec<char> = Vec::with_capacity(width * lines.len());
+
+        for (i, line) in lines.iter().enumerate() {
+            let count = line.chars().count();
+            if count != width {
+                let start = line
+                    .char_indices()
+                    .nth(width)
+                    .map_or(line.len(), |(i, _)| i);
+                let expected = format!("{} cells like the first row", width);
+                return Err(get_span_error(line, start, line.len(), &expected).at_line(i + 1));
+            }
+            cells.extend(line.chars());
+        }
+
+        Ok(Grid {
+            width,
+            height: lines.len(),
+            cells,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Point;
+
+    fn lines(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|line| line.to_string()).collect()
+    }
+
+    #[test]
+    fn test_from_lines() {
+        let grid = super::Grid::from_lines(&lines(&["#..", ".S#"])).unwrap();
+        assert_eq!((grid.width, grid.height), (3, 2));
+        assert_eq!(grid.get(Point { x: 1, y: 1 }), Some(&'S'));
+        assert_eq!(grid.get(Point { x: 3, y: 1 }), None);
+        assert_eq!(grid.get(Point { x: 0, y: -1 }), None);
+        assert_eq!(grid.find(|c| *c == 'S'), Some(Point { x: 1, y: 1 }));
+
+        let err = super::Grid::from_lines(&lines(&["#..", ".S#.."])).unwrap_err();
+        assert_eq!((err.line, err.column, err.width), (2, 4, 2));
+        assert_eq!(
+            err.get_message(),
+            "expected 3 cells like the first row, found '..'"
+        );
+
+        let err = super::Grid::from_lines(&lines(&["#..", "."])).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 2));
+    }
+
+    #[test]
+    fn test_set_and_points() {
+        let mut grid = super::Grid::new(2, 2, 0);
+        assert!(grid.set(Point { x: 1, y: 0 }, 5));
+        assert!(!grid.set(Point { x: 2, y: 0 }, 5));
+        *grid.get_mut(Point { x: 0, y: 1 }).unwrap() += 3;
+
+        let cells: Vec<(i64, i64, i32)> = grid
+            .points()
+            .map(|point| (point.x, point.y, *grid.get(point).unwrap()))
+            .collect();
+        assert_eq!(cells, vec![(0, 0, 0), (1, 0, 5), (0, 1, 3), (1, 1, 0)]);
+    }
+
+    #[test]
+    fn test_get_neighbours() {
+        let grid = super::Grid::new(3, 2, '.');
+        assert_eq!(
+            grid.get_neighbours(Point { x: 0, y: 0 }),
+            vec![Point { x: 1, y: 0 }, Point { x: 0, y: 1 }]
+        );
+        assert_eq!(grid.get_neighbours(Point { x: 1, y: 1 }).len(), 3);
+    }
+}
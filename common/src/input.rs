@@ -0,0 +1,266 @@
+use std::fmt::Display;
+use std::io::Read;
+use std::path::PathBuf;
+
+// The lines of one puzzle input and the name errors point at.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Input {
+    pub name: String,
+    pub lines: Vec<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct InputError {
+    pub path: String,
+    pub message: String,
+}
+
+impl Display for InputError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+// A path, or "-" for stdin.
+pub fn read_input(path: &str) -> Result<Input, InputError> {
+    let mut text = String::new();
+    let read = if path == "-" {
+        std::io::stdin().read_to_string(&mut text).map(|_| ())
+    } else {
+        std::fs::read_to_string(path).map(|content| text = content)
+    };
+
+    match read {
+        Ok(()) => Ok(Input {
+            name: if path == "-" { "<stdin>" } else { path }.to_string(),
+            lines: text.lines().map(String::from).collect(),
+        }),
+        Err(err) => Err(InputError {
+            path: path.to_string(),
+            message: err.to_string(),
+        }),
+    }
+}
+
+// Takes "--input <path>..." out of the arguments, every following argument up to the next option
+// is a path ("-" is stdin). Can be given several times, the paths stay in order.
+pub fn take_paths(args: &mut Vec<String>) -> Result<Vec<String>, String> {
+    let mut paths: Vec<String> = Vec::new();
+    let mut i = 0;
+
+    while i < args.len() {
+        if args[i] != "--input" {
+            i += 1;
+            continue;
+        }
+        let count = args[i + 1..]
+            .iter()
+            .take_while(|arg| *arg == "-" || !arg.starts_with('-'))
+            .count();
+        if count == 0 {
+            return Err("--input needs at least one file, or - for stdin".to_string());
+        }
+        paths.extend(args.drain(i..=i + count).skip(1));
+    }
+
+    Ok(paths)
+}
+
+// The files a day reads when it is not given any, found next to its Cargo.toml so the day can be
+// started from anywhere: InputFiles::new(env!("CARGO_MANIFEST_DIR"), "input1.txt", "input2.txt").
+#[derive(Debug, Clone, PartialEq)]
+pub struct InputFiles {
+    pub dir: PathBuf,
+    pub part1: String,
+    pub part2: String,
+}
+
+impl InputFiles {
+    pub fn new(dir: &str, part1: &str, part2: &str) -> InputFiles {
+        InputFiles {
+            dir: PathBuf::from(dir),
+            part1: part1.to_string(),
+            part2: part2.to_string(),
+        }
+    }
+
+    // The inputs of part 1 and part 2 for every path, both parts get the same one. Without paths
+    // it is the day's own files, named like in its directory.
+    pub fn get_inputs(&self, paths: &[String]) -> Vec<Result<(Input, Input), InputError>> {
+        if !paths.is_empty() {
+            return paths
+                .iter()
+                .map(|path| read_input(path).map(|input| (input.clone(), input)))
+                .collect();
+        }
+
+        let read = |name: &str| {
+            read_input(&self.dir.join(name).to_string_lossy()).map(|input| Input {
+                name: name.to_string(),
+                ..input
+            })
+        };
+        let inputs = match read(&self.part1) {
+            Ok(input1) if self.part1 == self.part2 => Ok((input1.clone(), input1)),
+            Ok(input1) => read(&self.part2).map(|input2| (input1, input2)),
+            Err(err) => Err(err),
+        };
+
+        vec![inputs]
+    }
+
+    // The lines of the day's own files of part 1 and part 2, for --bench. Panics like
+    // common::read_lines if they cannot be read.
+    pub fn read_lines(&self) -> (Vec<String>, Vec<String>) {
+        match self.get_inputs(&[]).remove(0) {
+            Ok((input1, input2)) => (input1.lines, input2.lines),
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    // Calls solve for every input, see get_inputs. With several paths the output of each starts
+    // with "==> path <==" like head(1). Errors are printed and the remaining inputs still run,
+    // false if any failed.
+    pub fn run<E: Display>(
+        &self,
+        paths: &[String],
+        mut solve: impl FnMut(&Input, &Input) -> Result<(), E>,
+    ) -> bool {
+        let mut ok = true;
+
+        for (i, inputs) in self.get_inputs(paths).iter().enumerate() {
+            if paths.len() > 1 {
+                if i > 0 {
+                    println!();
+                }
+                println!("==> {} <==", paths[i]);
+            }
+
+            let result = match inputs {
+                Ok((input1, input2)) => solve(input1, input2).map_err(|err| err.to_string()),
+                Err(err) => Err(format!("error: {}", err)),
+            };
+            if let Err(message) = result {
+                eprintln!("{}", message);
+                ok = false;
+            }
+        }
+
+        ok
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    fn get_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("aoc-input-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_take_paths() {
+        let mut rest = args(&[
+            "--explain",
+            "--input",
+            "a.txt",
+            "-",
+            "b.txt",
+            "--trace",
+            "csv",
+        ]);
+        assert_eq!(
+            super::take_paths(&mut rest),
+            Ok(args(&["a.txt", "-", "b.txt"]))
+        );
+        assert_eq!(rest, args(&["--explain", "--trace", "csv"]));
+
+        let mut rest = args(&["--input", "a.txt", "pair", "--input", "b.txt"]);
+        assert_eq!(
+            super::take_paths(&mut rest),
+            Ok(args(&["a.txt", "pair", "b.txt"]))
+        );
+        assert!(rest.is_empty());
+
+        let mut rest = args(&["render", "--svg", "out.svg"]);
+        assert_eq!(super::take_paths(&mut rest), Ok(Vec::new()));
+        assert_eq!(rest.len(), 3);
+
+        assert!(super::take_paths(&mut args(&["--input"])).is_err());
+        assert!(super::take_paths(&mut args(&["--input", "--bench"])).is_err());
+    }
+
+    #[test]
+    fn test_read_input() {
+        let dir = get_dir("read");
+        let path = dir.join("input.txt").to_string_lossy().to_string();
+        std::fs::write(&path, "32T3K 765\nT55J5 684\n").unwrap();
+
+        assert_eq!(
+            super::read_input(&path),
+            Ok(super::Input {
+                name: path.clone(),
+                lines: args(&["32T3K 765", "T55J5 684"]),
+            })
+        );
+
+        let missing = dir.join("missing.txt").to_string_lossy().to_string();
+        assert_eq!(super::read_input(&missing).unwrap_err().path, missing);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_get_inputs() {
+        let dir = get_dir("inputs");
+        std::fs::write(dir.join("input1.txt"), "1\n").unwrap();
+        std::fs::write(dir.join("input2.txt"), "2\n").unwrap();
+        let files = super::InputFiles::new(&dir.to_string_lossy(), "input1.txt", "input2.txt");
+        let names = |paths: &[String]| -> Vec<Result<(String, String, String), String>> {
+            files
+                .get_inputs(paths)
+                .into_iter()
+                .map(|inputs| {
+                    inputs
+                        .map(|(input1, input2)| (input1.name, input1.lines[0].clone(), input2.name))
+                        .map_err(|err| err.path)
+                })
+                .collect()
+        };
+
+        // the own files keep their short names
+        assert_eq!(
+            names(&[]),
+            vec![Ok((
+                "input1.txt".to_string(),
+                "1".to_string(),
+                "input2.txt".to_string()
+            ))]
+        );
+
+        // a given file is the input of both parts
+        let path = dir.join("input2.txt").to_string_lossy().to_string();
+        let missing = dir.join("missing.txt").to_string_lossy().to_string();
+        assert_eq!(
+            names(&[path.clone(), missing.clone()]),
+            vec![Ok((path.clone(), "2".to_string(), path)), Err(missing)]
+        );
+
+        assert_eq!(
+            files.read_lines(),
+            (vec!["1".to_string()], vec!["2".to_string()])
+        );
+
+        let files = super::InputFiles::new(&dir.to_string_lossy(), "input.txt", "input.txt");
+        assert!(files.get_inputs(&[])[0].is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
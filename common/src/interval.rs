@@ -0,0 +1,361 @@
+use std::ops::{Add, Range, Sub};
+
+// A set of integers kept as sorted half-open ranges. Overlapping and touching ranges are merged,
+// so 1..3 and 3..5 are stored as 1..5 and two sets with the same members are equal.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct IntervalSet<T> {
+    ranges: Vec<Range<T>>, // sorted, not empty, with gaps between them
+}
+
+impl<T> IntervalSet<T>
+where
+    T: Copy + Ord + Default + Add<Output = T> + Sub<Output = T>,
+{
+    pub fn new() -> IntervalSet<T> {
+        IntervalSet { ranges: Vec::new() }
+    }
+
+    pub fn from_ranges(ranges: impl IntoIterator<Item = Range<T>>) -> IntervalSet<T> {
+        let mut set = IntervalSet::new();
+        for range in ranges {
+            set.insert(range);
+        }
+        set
+    }
+
+    pub fn ranges(&self) -> &[Range<T>] {
+        &self.ranges
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    // how many values are in the set
+    pub fn get_length(&self) -> T {
+        self.ranges.iter().fold(T::default(), |length, range| {
+            length + (range.end - range.start)
+        })
+    }
+
+    pub fn insert(&mut self, range: Range<T>) {
+        if range.is_empty() {
+            return;
+        }
+        // every range from first to last overlaps or touches the new one
+        let first = self.ranges.partition_point(|r| r.end < range.start);
+        let last = self.ranges.partition_point(|r| r.start <= range.end);
+
+        let mut merged = range;
+        if first < last {
+            merged.start = merged.start.min(self.ranges[first].start);
+            merged.end = merged.end.max(self.ranges[last - 1].end);
+        }
+        self.ranges.splice(first..last, [merged]);
+    }
+
+    pub fn remove(&mut self, range: Range<T>) {
+        if range.is_empty() {
+            return;
+        }
+        // every range from first to last overlaps the removed one
+        let first = self.ranges.partition_point(|r| r.end <= range.start);
+        let last = self.ranges.partition_point(|r| r.start < range.end);
+        if first == last {
+            return;
+        }
+
+        let mut kept: Vec<Range<T>> = Vec::new();
+        if self.ranges[first].start < range.start {
+            kept.push(self.ranges[first].start..range.start);
+        }
+        if range.end < self.ranges[last - 1].end {
+            kept.push(range.end..self.ranges[last - 1].end);
+        }
+        self.ranges.splice(first..last, kept);
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let i = self.ranges.partition_point(|r| r.end <= value);
+        self.ranges.get(i).is_some_and(|r| r.start <= value)
+    }
+
+    // true if every value of range is in the set, an empty range always is
+    pub fn contains_range(&self, range: &Range<T>) -> bool {
+        if range.is_empty() {
+            return true;
+        }
+        let i = self.ranges.partition_point(|r| r.end <= range.start);
+        self.ranges
+            .get(i)
+            .is_some_and(|r| r.start <= range.start && range.end <= r.end)
+    }
+
+    pub fn union(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut union = self.clone();
+        for range in other.ranges.iter() {
+            union.insert(range.clone());
+        }
+        union
+    }
+
+    pub fn intersection(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut ranges: Vec<Range<T>> = Vec::new();
+        let (mut i, mut j) = (0, 0);
+
+        while i < self.ranges.len() && j < other.ranges.len() {
+            let (a, b) = (&self.ranges[i], &other.ranges[j]);
+            let (start, end) = (a.start.max(b.start), a.end.min(b.end));
+            if start < end {
+                ranges.push(start..end);
+            }
+            // the range that ends first cannot overlap anything else
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+
+        IntervalSet { ranges }
+    }
+
+    pub fn difference(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut difference = self.clone();
+        for range in other.ranges.iter() {
+            difference.remove(range.clone());
+        }
+        difference
+    }
+
+    // (the values below point, the values from point on)
+    pub fn split_at(&self, point: T) -> (IntervalSet<T>, IntervalSet<T>) {
+        let i = self.ranges.partition_point(|r| r.end <= point);
+        let mut below = self.ranges[..i].to_vec();
+        let mut above = self.ranges[i..].to_vec();
+
+        if let Some(range) = above.first_mut() {
+            if range.start < point {
+                below.push(range.start..point);
+                range.start = point;
+            }
+        }
+
+        (IntervalSet { ranges: below }, IntervalSet { ranges: above })
+    }
+
+    // The ranges of the set cut wherever one of the points falls inside them, e.g. to map each
+    // piece with the rule for the part of the number line it lies in.
+    pub fn split(&self, points: &[T]) -> Vec<Range<T>> {
+        let mut points = points.to_vec();
+        points.sort();
+        points.dedup();
+
+        let mut pieces: Vec<Range<T>> = Vec::new();
+        for range in self.ranges.iter() {
+            let mut start = range.start;
+            let first = points.partition_point(|point| *point <= range.start);
+            for point in points[first..]
+                .iter()
+                .take_while(|point| **point < range.end)
+            {
+                pieces.push(start..*point);
+                start = *point;
+            }
+            pieces.push(start..range.end);
+        }
+
+        pieces
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Range;
+
+    use super::IntervalSet;
+
+    // Every set of 0..SIZE is a bit mask, the sets are checked against plain bit operations.
+    const SIZE: i32 = 6;
+
+    fn get_mask(set: &IntervalSet<i32>) -> u32 {
+        (0..SIZE)
+            .filter(|value| set.contains(*value))
+            .fold(0, |mask, value| mask | 1 << value)
+    }
+
+    fn get_range_mask(range: &Range<i32>) -> u32 {
+        range.clone().fold(0, |mask, value| mask | 1 << value)
+    }
+
+    // The runs of set bits, inserted last to first so insert does more than append.
+    fn get_set(mask: u32) -> IntervalSet<i32> {
+        let mut ranges: Vec<Range<i32>> = Vec::new();
+        for value in 0..SIZE {
+            if mask & 1 << value != 0 {
+                match ranges.last_mut() {
+                    Some(range) if range.end == value => range.end += 1,
+                    _ => ranges.push(value..value + 1),
+                }
+            }
+        }
+        IntervalSet::from_ranges(ranges.into_iter().rev())
+    }
+
+    fn get_ranges() -> Vec<Range<i32>> {
+        (0..=SIZE)
+            .flat_map(|start| (start..=SIZE).map(move |end| start..end))
+            .collect()
+    }
+
+    // sorted, not empty, not touching, and only values of the domain
+    fn assert_valid(set: &IntervalSet<i32>) {
+        for range in set.ranges() {
+            assert!(range.start < range.end, "{:?}", set);
+            assert!(0 <= range.start && range.end <= SIZE, "{:?}", set);
+        }
+        for pair in set.ranges().windows(2) {
+            assert!(pair[0].end < pair[1].start, "{:?}", set);
+        }
+    }
+
+    #[test]
+    fn test_insert_and_remove() {
+        for mask in 0..1 << SIZE {
+            for range in get_ranges() {
+                let mut set = get_set(mask);
+                set.insert(range.clone());
+                assert_valid(&set);
+                assert_eq!(get_mask(&set), mask | get_range_mask(&range));
+                assert_eq!(set, get_set(mask | get_range_mask(&range)));
+
+                let mut set = get_set(mask);
+                set.remove(range.clone());
+                assert_valid(&set);
+                assert_eq!(set, get_set(mask & !get_range_mask(&range)));
+            }
+        }
+    }
+
+    #[test]
+    fn test_contains() {
+        for mask in 0..1 << SIZE {
+            let set = get_set(mask);
+            for value in -1..=SIZE {
+                let expected = (0..SIZE).contains(&value) && mask & 1 << value != 0;
+                assert_eq!(set.contains(value), expected);
+            }
+            for range in get_ranges() {
+                let bits = get_range_mask(&range);
+                assert_eq!(
+                    set.contains_range(&range),
+                    mask & bits == bits,
+                    "{:?}",
+                    range
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_set_operations() {
+        for a in 0..1 << SIZE {
+            for b in 0..1 << SIZE {
+                let (set_a, set_b) = (get_set(a), get_set(b));
+
+                let union = set_a.union(&set_b);
+                let intersection = set_a.intersection(&set_b);
+                let difference = set_a.difference(&set_b);
+                for set in [&union, &intersection, &difference] {
+                    assert_valid(set);
+                }
+                assert_eq!(union, get_set(a | b));
+                assert_eq!(intersection, get_set(a & b));
+                assert_eq!(difference, get_set(a & !b));
+            }
+        }
+    }
+
+    #[test]
+    fn test_get_length() {
+        for mask in 0..1 << SIZE {
+            let set = get_set(mask);
+            assert_eq!(set.get_length(), mask.count_ones() as i32);
+            assert_eq!(set.is_empty(), mask == 0);
+        }
+    }
+
+    #[test]
+    fn test_split_at() {
+        for mask in 0..1 << SIZE {
+            let set = get_set(mask);
+            for point in -1..=SIZE + 1 {
+                let (below, above) = set.split_at(point);
+                assert_valid(&below);
+                assert_valid(&above);
+                let low_bits = get_range_mask(&(0..point.clamp(0, SIZE)));
+                assert_eq!(below, get_set(mask & low_bits));
+                assert_eq!(above, get_set(mask & !low_bits));
+            }
+        }
+    }
+
+    #[test]
+    fn test_split() {
+        // every subset of cut points on every set
+        for mask in 0..1 << SIZE {
+            let set = get_set(mask);
+            for cuts in 0..1 << (SIZE + 1) {
+                let points: Vec<i32> = (0..=SIZE).filter(|point| cuts & 1 << point != 0).collect();
+                let pieces = set.split(&points);
+
+                // the pieces cover the set exactly, in order, and no piece spans a cut point
+                assert_eq!(IntervalSet::from_ranges(pieces.clone()), set);
+                for pair in pieces.windows(2) {
+                    assert!(pair[0].end <= pair[1].start);
+                }
+                for piece in pieces.iter() {
+                    assert!(!piece.is_empty());
+                    assert!(points
+                        .iter()
+                        .all(|point| !(piece.start < *point && *point < piece.end)));
+                }
+                // and a range is only cut at a point
+                let expected = set.ranges().len()
+                    + set
+                        .ranges()
+                        .iter()
+                        .map(|range| {
+                            points
+                                .iter()
+                                .filter(|p| range.start < **p && **p < range.end)
+                                .count()
+                        })
+                        .sum::<usize>();
+                assert_eq!(pieces.len(), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_large_values() {
+        let mut set = IntervalSet::from_ranges([79_u64..93, 55..68]);
+        set.insert(3_000_000_000..3_500_000_000);
+        set.remove(60..80);
+        assert_eq!(
+            set.ranges(),
+            &[55..60, 80..93, 3_000_000_000..3_500_000_000]
+        );
+        assert_eq!(set.get_length(), 5 + 13 + 500_000_000);
+        assert_eq!(
+            set.split(&[50, 90, 3_200_000_000]),
+            vec![
+                55..60,
+                80..90,
+                90..93,
+                3_000_000_000..3_200_000_000,
+                3_200_000_000..3_500_000_000
+            ]
+        );
+    }
+}
@@ -15,3 +15,18 @@ pub struct Point {
 }
 
 impl Copy for Point {}
+
+pub use grid::Grid;
+pub use interval::IntervalSet;
+
+pub mod bench;
+#[cfg(feature = "fetch")]
+pub mod fetch;
+pub mod grid;
+pub mod input;
+pub mod interval;
+pub mod parse;
+pub mod readme;
+pub mod search;
+#[cfg(feature = "fetch")]
+pub mod submit;
@@ -0,0 +1,452 @@
+use std::fmt::Display;
+use std::ops::Range;
+use std::str::FromStr;
+
+// Small helpers for the line formats of the puzzles. Errors carry the 1 based line and column,
+// line 0 means the text was parsed on its own; parse_lines fills in the line and the day its
+// input file.
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+    pub width: usize, // chars under the caret, at least 1
+    pub expected: String,
+    pub found: String,
+    pub text: String, // the whole offending line
+}
+
+impl ParseError {
+    pub fn in_file(mut self, file: &str) -> ParseError {
+        self.file = file.to_string();
+        self
+    }
+
+    pub fn at_line(mut self, line: usize) -> ParseError {
+        self.line = line;
+        self
+    }
+
+    pub fn get_message(&self) -> String {
+        match self.found.as_str() {
+            "" => format!("expected {}, found the end of the line", self.expected),
+            found => format!("expected {}, found '{}'", self.expected, found),
+        }
+    }
+}
+
+// Like rustc:
+//
+// error: expected u64, found '4x2'
+//   --> input.txt:14:6
+//    |
+// 14 | 0 11 4x2
+//    |      ^^^
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let file = if self.file.is_empty() {
+            "<input>"
+        } else {
+            &self.file
+        };
+        let number = if self.line > 0 {
+            self.line.to_string()
+        } else {
+            String::new()
+        };
+        let gutter = " ".repeat(number.len().max(1));
+
+        writeln!(f, "error: {}", self.get_message())?;
+        match self.line {
+            0 => writeln!(f, "{}--> {}:{}", gutter, file, self.column)?,
+            line => writeln!(f, "{}--> {}:{}:{}", gutter, file, line, self.column)?,
+        }
+        writeln!(f, "{} |", gutter)?;
+        writeln!(
+            f,
+            "{:>width$} | {}",
+            number,
+            self.text,
+            width = gutter.len()
+        )?;
+
+        // tabs stay tabs so the caret lines up with the text above
+        let padding: String = self
+            .text
+            .chars()
+            .take(self.column - 1)
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        write!(
+            f,
+            "{} | {}{}",
+            gutter,
+            padding,
+            "^".repeat(self.width.max(1))
+        )
+    }
+}
+
+// The error for the word at text[offset..] not being what was expected.
+pub fn get_error(text: &str, offset: usize, expected: &str) -> ParseError {
+    let rest = &text[offset..];
+    let end = match rest.find(char::is_whitespace) {
+        Some(0) => rest.chars().next().map_or(0, char::len_utf8),
+        Some(end) => end,
+        None => rest.len(),
+    };
+    get_span_error(text, offset, offset + end, expected)
+}
+
+// The error for text[start..end] not being what was expected.
+pub fn get_span_error(text: &str, start: usize, end: usize, expected: &str) -> ParseError {
+    ParseError {
+        file: String::new(),
+        line: 0,
+        column: text[..start].chars().count() + 1,
+        width: text[start..end].chars().count().max(1),
+        expected: expected.to_string(),
+        found: text[start..end].to_string(),
+        text: text.to_string(),
+    }
+}
+
+// Parses every line that is not blank, errors get the line number they happened on.
+pub fn parse_lines<T>(
+    lines: &[String],
+    parse: impl Fn(&str) -> Result<T, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| parse(line).map_err(|err| err.at_line(i + 1)))
+        .collect()
+}
+
+// Every integer in the line, a '-' right before the digits makes it negative unless it follows
+// a digit ("1-2" is 1 and 2). "x=-3, y=4" -> [-3, 4]
+pub fn get_ints(line: &str) -> Vec<i64> {
+    let bytes = line.as_bytes();
+    let mut ints: Vec<i64> = Vec::new();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if !bytes[i].is_ascii_digit() {
+            i += 1;
+            continue;
+        }
+        let negative = i > 0 && bytes[i - 1] == b'-' && (i < 2 || !bytes[i - 2].is_ascii_digit());
+        let start = i;
+        while i < bytes.len() && bytes[i].is_ascii_digit() {
+            i += 1;
+        }
+
+        // too many digits for an i64 are left out rather than wrapped
+        if let Ok(value) = line[start..i].parse::<i64>() {
+            ints.push(if negative { -value } else { value });
+        }
+    }
+
+    ints
+}
+
+// Every word of line[range] as T, e.g. the numbers after "seeds:".
+pub fn get_numbers<T: FromStr>(line: &str, range: Range<usize>) -> Result<Vec<T>, ParseError> {
+    let mut offset = range.start;
+
+    line[range]
+        .split(' ')
+        .map(|word| {
+            let begin = offset;
+            offset += word.len() + 1;
+            (begin, word)
+        })
+        .filter(|(_, word)| !word.is_empty())
+        .map(|(begin, word)| {
+            word.parse::<T>()
+                .map_err(|_| get_span_error(line, begin, begin + word.len(), get_type_name::<T>()))
+        })
+        .collect()
+}
+
+// The groups of lines between blank lines, without the blank lines and empty groups, each with
+// the 0 based index of its first line.
+pub fn get_blocks(lines: &[String]) -> Vec<(usize, &[String])> {
+    let mut blocks: Vec<(usize, &[String])> = Vec::new();
+    let mut start = 0;
+
+    for (i, line) in lines.iter().enumerate() {
+        if line.trim().is_empty() {
+            if start < i {
+                blocks.push((start, &lines[start..i]));
+            }
+            start = i + 1;
+        }
+    }
+    if start < lines.len() {
+        blocks.push((start, &lines[start..]));
+    }
+
+    blocks
+}
+
+// Moves an error of parse_lines on a block to the line numbers of the whole input.
+pub fn shift_lines(mut err: ParseError, first: usize) -> ParseError {
+    err.line += first;
+    err
+}
+
+// A line format where every {} is a field and the rest has to match exactly,
+// e.g. "{} = ({}, {})" for "AAA = (BBB, CCC)". A field ends where the text after it starts.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pattern {
+    literals: Vec<String>, // one more than there are fields
+}
+
+// The fields of one line matched against a pattern, as byte ranges into the line.
+#[derive(Debug, PartialEq)]
+pub struct Captures<'a> {
+    line: &'a str,
+    fields: Vec<(usize, usize)>,
+}
+
+impl Pattern {
+    pub fn new(pattern: &str) -> Pattern {
+        Pattern {
+            literals: pattern.split("{}").map(String::from).collect(),
+        }
+    }
+
+    pub fn captures<'a>(&self, line: &'a str) -> Result<Captures<'a>, ParseError> {
+        let first = &self.literals[0];
+        if !line.starts_with(first.as_str()) {
+            return Err(get_error(line, 0, &format!("'{}'", first)));
+        }
+
+        let mut offset = first.len();
+        let mut fields: Vec<(usize, usize)> = Vec::new();
+        for literal in &self.literals[1..] {
+            let end = if literal.is_empty() {
+                line.len()
+            } else {
+                offset
+                    + line[offset..]
+                        .find(literal.as_str())
+                        .ok_or_else(|| get_literal_error(line, offset, literal))?
+            };
+            if end == offset {
+                return Err(get_error(line, offset, "a value"));
+            }
+            fields.push((offset, end));
+            offset = end + literal.len();
+        }
+
+        if offset < line.len() {
+            return Err(get_error(line, offset, "the end of the line"));
+        }
+
+        Ok(Captures { line, fields })
+    }
+}
+
+// Points at where the longest start of the literal that is in the line stops matching,
+// "AAA = BBB" against " = (" expects '(' before BBB.
+fn get_literal_error(line: &str, offset: usize, literal: &str) -> ParseError {
+    let found = (1..literal.len())
+        .rev()
+        .filter(|&len| literal.is_char_boundary(len))
+        .find_map(|len| {
+            line[offset..]
+                .find(&literal[..len])
+                .map(|start| (offset + start + len, &literal[len..]))
+        });
+
+    match found {
+        Some((offset, rest)) => get_error(line, offset, &format!("'{}'", rest)),
+        None => get_error(line, line.len(), &format!("'{}'", literal)),
+    }
+}
+
+impl<'a> Captures<'a> {
+    pub fn len(&self) -> usize {
+        self.fields.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    pub fn get_str(&self, i: usize) -> &'a str {
+        let (start, end) = self.fields[i];
+        &self.line[start..end]
+    }
+
+    // Where the field is in the line, to point errors about its value at it.
+    pub fn get_range(&self, i: usize) -> Range<usize> {
+        let (start, end) = self.fields[i];
+        start..end
+    }
+
+    // The field as T, an error points at the field and names the type it should have been.
+    pub fn get<T: FromStr>(&self, i: usize) -> Result<T, ParseError> {
+        let (start, end) = self.fields[i];
+        self.line[start..end]
+            .parse::<T>()
+            .map_err(|_| get_span_error(self.line, start, end, get_type_name::<T>()))
+    }
+}
+
+// "u64" rather than "core::primitive::u64"
+fn get_type_name<T>() -> &'static str {
+    let name = std::any::type_name::<T>();
+    name.rsplit("::").next().unwrap_or(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ParseError, Pattern};
+
+    // (line, column, width, expected, found) of an error
+    fn error(
+        result: Result<impl std::fmt::Debug, ParseError>,
+    ) -> (usize, usize, usize, String, String) {
+        let err = result.unwrap_err();
+        (err.line, err.column, err.width, err.expected, err.found)
+    }
+
+    fn fields(
+        line: usize,
+        column: usize,
+        width: usize,
+        expected: &str,
+        found: &str,
+    ) -> (usize, usize, usize, String, String) {
+        (line, column, width, expected.to_string(), found.to_string())
+    }
+
+    #[test]
+    fn test_get_ints() {
+        assert_eq!(super::get_ints("seeds: 79 14 55 13"), vec![79, 14, 55, 13]);
+        assert_eq!(super::get_ints("x=-3, y=4..-12"), vec![-3, 4, -12]);
+        assert_eq!(super::get_ints("-1 2-3 --4"), vec![-1, 2, 3, -4]);
+        assert_eq!(
+            super::get_ints("Card   1: 41 48 | 83 86"),
+            vec![1, 41, 48, 83, 86]
+        );
+        assert_eq!(super::get_ints("99999999999999999999 and 7"), vec![7]);
+        assert!(super::get_ints("no numbers - here").is_empty());
+    }
+
+    #[test]
+    fn test_get_numbers() {
+        assert_eq!(
+            super::get_numbers::<u64>("seeds: 79 14  55 13", 6..19),
+            Ok(vec![79, 14, 55, 13])
+        );
+        assert_eq!(
+            error(super::get_numbers::<i32>("1 -2 x3", 0..7)),
+            fields(0, 6, 2, "i32", "x3")
+        );
+        assert_eq!(super::get_numbers::<u8>("1 2 | x", 0..4), Ok(vec![1, 2]));
+        assert_eq!(super::get_numbers::<u8>("", 0..0), Ok(vec![]));
+    }
+
+    #[test]
+    fn test_get_blocks() {
+        let lines: Vec<String> = ["", "a", "b", "", "  ", "c", ""]
+            .iter()
+            .map(|line| line.to_string())
+            .collect();
+        assert_eq!(
+            super::get_blocks(&lines),
+            vec![(1, &lines[1..3]), (5, &lines[5..6])]
+        );
+        assert_eq!(super::get_blocks(&lines[1..3]), vec![(0, &lines[1..3])]);
+        assert!(super::get_blocks(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_pattern() {
+        let pattern = Pattern::new("{} = ({}, {})");
+        let captures = pattern.captures("AAA = (BBB, CCC)").unwrap();
+        assert_eq!(captures.len(), 3);
+        assert_eq!(
+            (0..3).map(|i| captures.get_str(i)).collect::<Vec<&str>>(),
+            vec!["AAA", "BBB", "CCC"]
+        );
+        assert_eq!(captures.get_range(2), 12..15);
+
+        assert_eq!(
+            error(pattern.captures("AAA = BBB, CCC)")),
+            fields(0, 7, 4, "'('", "BBB,")
+        );
+        assert_eq!(
+            error(pattern.captures("AAA = (BBB)")),
+            fields(0, 12, 1, "', '", "")
+        );
+        assert_eq!(
+            error(pattern.captures(" = (BBB, CCC)")),
+            fields(0, 1, 1, "a value", " ")
+        );
+        assert_eq!(
+            error(pattern.captures("AAA = (BBB, CCC) x")),
+            fields(0, 17, 1, "the end of the line", " ")
+        );
+
+        let pattern = Pattern::new("Game {}: {}");
+        let captures = pattern.captures("Game 12: 3 blue").unwrap();
+        assert_eq!(captures.get::<u32>(0), Ok(12));
+        assert_eq!(captures.get_str(1), "3 blue");
+        assert_eq!(
+            error(captures.get::<u32>(1)),
+            fields(0, 10, 6, "u32", "3 blue")
+        );
+        assert_eq!(
+            error(pattern.captures("Gaem 12: 3 blue")),
+            fields(0, 1, 4, "'Game '", "Gaem")
+        );
+    }
+
+    #[test]
+    fn test_parse_lines() {
+        let lines: Vec<String> = ["1 -> 2", "", "3 -> x"]
+            .iter()
+            .map(|line| line.to_string())
+            .collect();
+        let pattern = Pattern::new("{} -> {}");
+        let parse = |line: &str| -> Result<(u8, u8), ParseError> {
+            let captures = pattern.captures(line)?;
+            Ok((captures.get(0)?, captures.get(1)?))
+        };
+
+        assert_eq!(super::parse_lines(&lines[..2], parse), Ok(vec![(1, 2)]));
+        assert_eq!(
+            error(super::parse_lines(&lines, parse)),
+            fields(3, 6, 1, "u8", "x")
+        );
+    }
+
+    #[test]
+    fn test_render() {
+        let err = super::get_error("0 11 4x2", 5, "u64")
+            .at_line(14)
+            .in_file("day5/input.txt");
+        assert_eq!(
+            err.to_string(),
+            "error: expected u64, found '4x2'\n  --> day5/input.txt:14:6\n   |\n14 | 0 11 4x2\n   |      ^^^"
+        );
+
+        // the caret keeps the tabs of the line and sits after its end if something is missing
+        let err = super::get_error("\tab", 3, "'c'").at_line(2);
+        assert_eq!(
+            err.to_string(),
+            "error: expected 'c', found the end of the line\n --> <input>:2:4\n  |\n2 | \tab\n  | \t  ^"
+        );
+
+        let err = super::get_error("é x", 3, "a number");
+        assert_eq!(err.column, 3);
+        assert!(err.to_string().ends_with("\n  | é x\n  |   ^"));
+    }
+}
@@ -0,0 +1,360 @@
+use std::fmt::Write;
+use std::path::Path;
+
+// The readme is the puzzle text as it is copied from the site, the examples are read from it:
+// - a block is the run of lines that are not prose after a prose line ending with ':' or after
+//   "For example:", blank lines inside it belong to it
+// - the answer of a part is the last number, outside of parentheses, of the last line before
+//   the question of the part that talks about the example ("In this example, ... 288.")
+// - example inputs are the blocks introduced by "For example:" (or "... example:") and the
+//   block an answer line introduces ("here is a situation that takes 6 steps ...:")
+// - an answer belongs to the block its line introduces, otherwise to the last example input
+//   above it, so part two checks the input of part one when it has none of its own
+
+#[derive(Debug, PartialEq)]
+pub struct Example {
+    pub line: usize, // 1 based line of the first input line
+    pub lines: Vec<String>,
+    pub answers: Vec<Answer>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Answer {
+    pub part: u32,
+    pub answer: String,
+}
+
+#[derive(Debug)]
+struct Block {
+    intro: usize, // 0 based line numbers
+    first: usize,
+    lines: Vec<String>,
+}
+
+// a sentence: at least three lowercase words and punctuation at the end, unlike "Game 1: 3 blue"
+fn is_prose(line: &str) -> bool {
+    let ends_sentence = line
+        .trim_end()
+        .trim_end_matches(')')
+        .ends_with(['.', ':', '!', '?']);
+    let words = line
+        .split_whitespace()
+        .map(|word| word.trim_matches(|c: char| !c.is_alphanumeric()))
+        .filter(|word| !word.is_empty() && word.chars().all(|c| c.is_lowercase()))
+        .count();
+
+    ends_sentence && words >= 3
+}
+
+fn is_example_intro(line: &str) -> bool {
+    line.trim_end().to_lowercase().ends_with("example:")
+}
+
+fn get_blocks(lines: &[&str]) -> Vec<Block> {
+    let mut blocks: Vec<Block> = Vec::new();
+
+    for (intro, line) in lines.iter().enumerate() {
+        if !line.trim_end().ends_with(':') || !(is_prose(line) || is_example_intro(line)) {
+            continue;
+        }
+
+        let mut block = Block {
+            intro,
+            first: intro + 1,
+            lines: Vec::new(),
+        };
+        for line in lines.iter().skip(intro + 1) {
+            if is_prose(line) || line.starts_with("--- ") {
+                break;
+            }
+            if block.lines.is_empty() && line.trim().is_empty() {
+                block.first += 1;
+            } else {
+                block.lines.push(line.to_string());
+            }
+        }
+        while block
+            .lines
+            .last()
+            .is_some_and(|line| line.trim().is_empty())
+        {
+            block.lines.pop();
+        }
+
+        if !block.lines.is_empty() {
+            blocks.push(block);
+        }
+    }
+
+    blocks
+}
+
+// the numbers of the line that are not in parentheses
+fn get_numbers(line: &str) -> Vec<&str> {
+    let mut numbers: Vec<&str> = Vec::new();
+    let mut depth = 0;
+    let mut start: Option<usize> = None;
+
+    for (i, c) in line.char_indices().chain([(line.len(), ' ')]) {
+        if c.is_alphanumeric() {
+            start.get_or_insert(i);
+            continue;
+        }
+        if let Some(start) = start.take() {
+            let word = &line[start..i];
+            if depth == 0 && word.chars().all(|c| c.is_ascii_digit()) {
+                numbers.push(word);
+            }
+        }
+        match c {
+            '(' => depth += 1,
+            ')' => depth = (depth - 1).max(0),
+            _ => (),
+        }
+    }
+
+    numbers
+}
+
+// (line, answer) of the part in lines[start..end]
+fn get_answer(lines: &[&str], start: usize, end: usize) -> Option<(usize, String)> {
+    let question = (start..end)
+        .rev()
+        .find(|&i| lines[i].trim_end().ends_with('?'))
+        .unwrap_or(end);
+
+    (start..question).rev().find_map(|i| {
+        let line = lines[i];
+        if !is_prose(line) || !line.to_lowercase().contains("example") {
+            return None;
+        }
+        get_numbers(line)
+            .last()
+            .map(|number| (i, number.to_string()))
+    })
+}
+
+pub fn get_examples(text: &str) -> Vec<Example> {
+    let lines: Vec<&str> = text.lines().collect();
+    let blocks = get_blocks(&lines);
+
+    let part_two = lines
+        .iter()
+        .position(|line| line.starts_with("--- Part Two ---"))
+        .unwrap_or(lines.len());
+    let answers: Vec<(u32, usize, String)> = [(1, 0, part_two), (2, part_two, lines.len())]
+        .into_iter()
+        .filter_map(|(part, start, end)| {
+            get_answer(&lines, start, end).map(|(line, answer)| (part, line, answer))
+        })
+        .collect();
+
+    let is_input = |block: &Block| {
+        is_example_intro(lines[block.intro])
+            || answers.iter().any(|(_, line, _)| *line == block.intro)
+    };
+    let inputs: Vec<&Block> = blocks.iter().filter(|block| is_input(block)).collect();
+    let mut examples: Vec<Example> = inputs
+        .iter()
+        .map(|block| Example {
+            line: block.first + 1,
+            lines: block.lines.clone(),
+            answers: Vec::new(),
+        })
+        .collect();
+
+    for (part, line, answer) in answers {
+        let example = match inputs.iter().position(|block| block.intro == line) {
+            Some(example) => Some(example),
+            None => inputs.iter().rposition(|block| block.intro < line),
+        };
+        if let Some(example) = example {
+            examples[example].answers.push(Answer { part, answer });
+        }
+    }
+
+    examples
+}
+
+// Rust source with a get_example_input(n) fixture (1 based, in the order of the puzzle text)
+// and one test per answer. part1 and part2 return a Result, a parse error fails the test with
+// its rendered snippet.
+pub fn get_example_tests(examples: &[Example]) -> String {
+    let mut source = String::new();
+
+    writeln!(source, "#[allow(dead_code)]").unwrap();
+    writeln!(source, "fn get_example_input(n: usize) -> Vec<String> {{").unwrap();
+    writeln!(source, "    let examples: &[&[&str]] = &[").unwrap();
+    for example in examples.iter() {
+        writeln!(source, "        &{:?},", example.lines).unwrap();
+    }
+    writeln!(source, "    ];").unwrap();
+    writeln!(
+        source,
+        "    match n.checked_sub(1).and_then(|i| examples.get(i)) {{"
+    )
+    .unwrap();
+    writeln!(
+        source,
+        "        Some(lines) => lines.iter().map(|line| line.to_string()).collect(),"
+    )
+    .unwrap();
+    writeln!(
+        source,
+        "        None => panic!(\"the readme has no example {{}}\", n),"
+    )
+    .unwrap();
+    writeln!(source, "    }}").unwrap();
+    writeln!(source, "}}").unwrap();
+
+    for (i, example) in examples.iter().enumerate() {
+        for answer in example.answers.iter() {
+            writeln!(source).unwrap();
+            writeln!(source, "#[test]").unwrap();
+            writeln!(source, "fn test_readme_part{}_{}() {{", answer.part, i + 1).unwrap();
+            writeln!(
+                source,
+                "    match super::part{}(get_example_input({})) {{",
+                answer.part,
+                i + 1
+            )
+            .unwrap();
+            writeln!(
+                source,
+                "        Ok(answer) => assert_eq!(answer.to_string(), {:?}),",
+                answer.answer
+            )
+            .unwrap();
+            writeln!(source, "        Err(err) => panic!(\"\\n{{}}\", err),").unwrap();
+            writeln!(source, "    }}").unwrap();
+            writeln!(source, "}}").unwrap();
+        }
+    }
+
+    source
+}
+
+// For build scripts: include!(concat!(env!("OUT_DIR"), "/readme_examples.rs")) in the tests.
+pub fn write_example_tests(readme: &str) {
+    println!("cargo:rerun-if-changed={}", readme);
+
+    let text = std::fs::read_to_string(readme).unwrap();
+    let examples = get_examples(&text);
+    if examples.is_empty() {
+        println!(
+            "cargo:warning={} has no examples, paste the puzzle text to get example tests",
+            readme
+        );
+    }
+
+    let out = Path::new(&std::env::var("OUT_DIR").unwrap()).join("readme_examples.rs");
+    std::fs::write(out, get_example_tests(&examples)).unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Answer;
+
+    fn get_readme() -> String {
+        [
+            "--- Day 0: Test ---",
+            "You get a list of numbers (your puzzle input). For example:",
+            "",
+            "1 2",
+            "",
+            "3",
+            "The sum of the numbers is what you are after, it looks like this:",
+            "",
+            "1 + 2 + 3",
+            "In this example, the sum is 6 (1 + 2 + 3).",
+            "What is the sum of your numbers?",
+            "",
+            "--- Part Two ---",
+            "Now each line is a number. For example, here is one that gives 12:",
+            "",
+            "x 1 2",
+            "Each has 10 added, so ... 7 is now 17.",
+            "What is the new sum?",
+        ]
+        .join("\n")
+    }
+
+    #[test]
+    fn test_is_prose() {
+        assert!(super::is_prose("This document describes three races:"));
+        assert!(super::is_prose("Step 0: You are at 11A and 22A."));
+        assert!(super::is_prose("(In the example above, it would be 1030.)"));
+        assert!(!super::is_prose("Time:      7  15   30"));
+        assert!(!super::is_prose("AAA = (BBB, CCC)"));
+        assert!(!super::is_prose("Game 1: 3 blue, 4 red; 1 red, 2 green"));
+        assert!(!super::is_prose("For example:"));
+        assert!(super::is_example_intro("For example:"));
+    }
+
+    #[test]
+    fn test_get_numbers() {
+        assert_eq!(super::get_numbers("you get 288 (4 * 8 * 9)."), vec!["288"]);
+        assert_eq!(
+            super::get_numbers("galaxies 5 and 9: 11A, 22, (1 (2) 3) 4"),
+            vec!["5", "9", "22", "4"]
+        );
+        assert!(super::get_numbers("RLRLRL... and so on.").is_empty());
+    }
+
+    #[test]
+    fn test_get_examples() {
+        let examples = super::get_examples(&get_readme());
+
+        assert_eq!(examples.len(), 2);
+        assert_eq!(examples[0].line, 4);
+        assert_eq!(examples[0].lines, vec!["1 2", "", "3"]);
+        assert_eq!(
+            examples[0].answers,
+            vec![Answer {
+                part: 1,
+                answer: "6".to_string()
+            }]
+        );
+        // the answer line introduces the input, the number after it is not the answer
+        assert_eq!(examples[1].lines, vec!["x 1 2"]);
+        assert_eq!(
+            examples[1].answers,
+            vec![Answer {
+                part: 2,
+                answer: "12".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn test_get_examples_part_one_input() {
+        // without an input of its own part two checks the one of part one
+        let readme = get_readme().replace("For example, here is one that gives 12:", "");
+        let readme = readme.replace("... 7 is now 17.", "the example gives 26.");
+        let examples = super::get_examples(&readme);
+
+        assert_eq!(examples.len(), 1);
+        assert_eq!(
+            examples[0].answers.last(),
+            Some(&Answer {
+                part: 2,
+                answer: "26".to_string()
+            })
+        );
+
+        assert!(super::get_examples("--- Day 0: Test ---\nNo examples here?").is_empty());
+    }
+
+    #[test]
+    fn test_get_example_tests() {
+        let source = super::get_example_tests(&super::get_examples(&get_readme()));
+
+        assert!(source.contains("        &[\"1 2\", \"\", \"3\"],\n        &[\"x 1 2\"],\n    ];"));
+        assert!(source.contains(
+            "fn test_readme_part1_1() {\n    match super::part1(get_example_input(1)) {\n        Ok(answer) => assert_eq!(answer.to_string(), \"6\"),\n        Err(err) => panic!(\"\\n{}\", err),\n    }\n}"
+        ));
+        assert!(source.contains(
+            "fn test_readme_part2_2() {\n    match super::part2(get_example_input(2)) {"
+        ));
+    }
+}
@@ -0,0 +1,395 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+
+// What a search reached: the cost of every visited node, the order they were visited in and
+// where each was reached from, to rebuild the paths.
+#[derive(Debug, Clone)]
+pub struct Search<N> {
+    pub distances: HashMap<N, u64>,
+    pub order: Vec<N>,
+    previous: HashMap<N, N>,
+}
+
+impl<N: Clone + Eq + Hash> Search<N> {
+    fn new() -> Search<N> {
+        Search {
+            distances: HashMap::new(),
+            order: Vec::new(),
+            previous: HashMap::new(),
+        }
+    }
+
+    pub fn get_distance(&self, node: &N) -> Option<u64> {
+        self.distances.get(node).copied()
+    }
+
+    // The nodes from the start to node, both included. None if node was not reached.
+    pub fn get_path(&self, node: &N) -> Option<Vec<N>> {
+        if !self.distances.contains_key(node) {
+            return None;
+        }
+
+        let mut path = vec![node.clone()];
+        while let Some(previous) = self.previous.get(path.last().unwrap()) {
+            path.push(previous.clone());
+        }
+        path.reverse();
+
+        Some(path)
+    }
+}
+
+// Fewest steps from any of the starts to every reachable node.
+pub fn bfs<N, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut neighbours: impl FnMut(&N) -> I,
+) -> Search<N>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut search = Search::new();
+    let mut queue: VecDeque<N> = VecDeque::new();
+    for start in starts {
+        if !search.distances.contains_key(&start) {
+            search.distances.insert(start.clone(), 0);
+            queue.push_back(start);
+        }
+    }
+
+    while let Some(current) = queue.pop_front() {
+        let distance = search.distances[&current];
+        for next in neighbours(&current) {
+            if !search.distances.contains_key(&next) {
+                search.distances.insert(next.clone(), distance + 1);
+                search.previous.insert(next.clone(), current.clone());
+                queue.push_back(next);
+            }
+        }
+        search.order.push(current);
+    }
+
+    search
+}
+
+// Depth first, neighbours in the order they are given. The distances are the steps along the
+// path the search took, not the fewest possible.
+pub fn dfs<N, I>(start: N, mut neighbours: impl FnMut(&N) -> I) -> Search<N>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut search = Search::new();
+    let mut visited: HashSet<N> = HashSet::new();
+    let mut stack: Vec<(N, Option<N>, u64)> = vec![(start, None, 0)];
+
+    while let Some((current, from, distance)) = stack.pop() {
+        if !visited.insert(current.clone()) {
+            continue;
+        }
+        search.distances.insert(current.clone(), distance);
+        if let Some(from) = from {
+            search.previous.insert(current.clone(), from);
+        }
+
+        let next: Vec<N> = neighbours(&current)
+            .into_iter()
+            .filter(|next| !visited.contains(next))
+            .collect();
+        for next in next.into_iter().rev() {
+            stack.push((next, Some(current.clone()), distance + 1));
+        }
+        search.order.push(current);
+    }
+
+    search
+}
+
+// Lowest total cost from any of the starts to every reachable node, neighbours come with the
+// cost of the step to them.
+pub fn dijkstra<N, I>(
+    starts: impl IntoIterator<Item = N>,
+    neighbours: impl FnMut(&N) -> I,
+) -> Search<N>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = (N, u64)>,
+{
+    best_first(starts, neighbours, |_| 0, |_| false).0
+}
+
+// The cheapest path from start to the first node that is_goal accepts, as (cost, path).
+// The heuristic estimates the cost left to a goal and must never be too high, 0 is Dijkstra.
+pub fn astar<N, I>(
+    start: N,
+    is_goal: impl FnMut(&N) -> bool,
+    neighbours: impl FnMut(&N) -> I,
+    heuristic: impl FnMut(&N) -> u64,
+) -> Option<(u64, Vec<N>)>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = (N, u64)>,
+{
+    let (search, goal) = best_first([start], neighbours, heuristic, is_goal);
+    let goal = goal?;
+
+    Some((search.distances[&goal], search.get_path(&goal)?))
+}
+
+// Expands the node with the lowest cost + heuristic first, until a goal is expanded or
+// everything reachable is. Nodes that are found again for less are expanded again.
+fn best_first<N, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut neighbours: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> u64,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> (Search<N>, Option<N>)
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = (N, u64)>,
+{
+    let mut search = Search::new();
+    // the heap holds (cost + heuristic, index into queued), so N does not need to be Ord
+    let mut queued: Vec<(N, u64)> = Vec::new();
+    let mut heap: BinaryHeap<Reverse<(u64, usize)>> = BinaryHeap::new();
+
+    for start in starts {
+        if !search.distances.contains_key(&start) {
+            search.distances.insert(start.clone(), 0);
+            heap.push(Reverse((heuristic(&start), queued.len())));
+            queued.push((start, 0));
+        }
+    }
+
+    while let Some(Reverse((_, index))) = heap.pop() {
+        let (current, cost) = queued[index].clone();
+        if search.distances[&current] < cost {
+            continue; // found for less since it was queued
+        }
+        if is_goal(&current) {
+            search.order.push(current.clone());
+            return (search, Some(current));
+        }
+
+        for (next, step) in neighbours(&current) {
+            let next_cost = cost + step;
+            if search
+                .distances
+                .get(&next)
+                .is_some_and(|&known| known <= next_cost)
+            {
+                continue;
+            }
+            search.distances.insert(next.clone(), next_cost);
+            search.previous.insert(next.clone(), current.clone());
+            heap.push(Reverse((next_cost + heuristic(&next), queued.len())));
+            queued.push((next, next_cost));
+        }
+        search.order.push(current);
+    }
+
+    (search, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::{Grid, Point};
+
+    fn get_maze() -> Grid<char> {
+        let lines: Vec<String> = [
+            "S.#.....", //
+            ".##.###.", //
+            "....#...", //
+            ".##...#E", //
+            "...#.#..", //
+        ]
+        .iter()
+        .map(|line| line.to_string())
+        .collect();
+        Grid::from_lines(&lines).unwrap()
+    }
+
+    fn get_open_neighbours(grid: &Grid<char>, point: &Point) -> Vec<Point> {
+        grid.get_neighbours(*point)
+            .into_iter()
+            .filter(|next| grid.get(*next) != Some(&'#'))
+            .collect()
+    }
+
+    // a path has to start at a start, end at the node and only take steps the graph has
+    fn assert_path(path: &[Point], start: Point, end: Point, grid: &Grid<char>) {
+        assert_eq!(path.first(), Some(&start));
+        assert_eq!(path.last(), Some(&end));
+        for step in path.windows(2) {
+            assert!(get_open_neighbours(grid, &step[0]).contains(&step[1]));
+        }
+    }
+
+    // tiny pseudo random graphs, weights 1 to 9 with some edges missing
+    fn get_graphs() -> Vec<HashMap<u32, Vec<(u32, u64)>>> {
+        let mut seed: u64 = 7;
+        let mut random = move |n: u64| {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (seed >> 33) % n
+        };
+
+        let mut graphs = Vec::new();
+        for _ in 0..50 {
+            let size = 2 + random(6) as u32;
+            let mut graph: HashMap<u32, Vec<(u32, u64)>> = HashMap::new();
+            for from in 0..size {
+                let edges = graph.entry(from).or_default();
+                for to in 0..size {
+                    if to != from && random(3) == 0 {
+                        edges.push((to, 1 + random(9)));
+                    }
+                }
+            }
+            graphs.push(graph);
+        }
+        graphs
+    }
+
+    // Bellman-Ford, slow but obviously right
+    fn get_costs(graph: &HashMap<u32, Vec<(u32, u64)>>) -> HashMap<u32, u64> {
+        let mut costs: HashMap<u32, u64> = HashMap::from([(0, 0)]);
+        for _ in 0..graph.len() {
+            for (from, edges) in graph {
+                if let Some(&cost) = costs.get(from) {
+                    for (to, step) in edges {
+                        let entry = costs.entry(*to).or_insert(u64::MAX);
+                        *entry = (*entry).min(cost + step);
+                    }
+                }
+            }
+        }
+        costs
+    }
+
+    #[test]
+    fn test_bfs() {
+        let grid = get_maze();
+        let start = grid.find(|c| *c == 'S').unwrap();
+        let end = grid.find(|c| *c == 'E').unwrap();
+        let search = super::bfs([start], |point| get_open_neighbours(&grid, point));
+
+        assert_eq!(search.get_distance(&end), Some(12));
+        assert_eq!(search.get_distance(&Point { x: 2, y: 0 }), None);
+        assert_eq!(search.order[0], start);
+        assert_eq!(search.order.len(), 28);
+        let path = search.get_path(&end).unwrap();
+        assert_eq!(path.len(), 13);
+        assert_path(&path, start, end, &grid);
+
+        // several starts, every node counts from the nearest
+        let other = Point { x: 0, y: 4 };
+        let search = super::bfs([start, other], |point| get_open_neighbours(&grid, point));
+        assert_eq!(search.get_distance(&Point { x: 0, y: 3 }), Some(1));
+        assert_eq!(search.get_path(&other), Some(vec![other]));
+    }
+
+    #[test]
+    fn test_dfs() {
+        let grid = get_maze();
+        let start = grid.find(|c| *c == 'S').unwrap();
+        let end = grid.find(|c| *c == 'E').unwrap();
+        let search = super::dfs(start, |point| get_open_neighbours(&grid, point));
+        let reachable = super::bfs([start], |point| get_open_neighbours(&grid, point));
+
+        assert_eq!(search.order.len(), reachable.order.len());
+        // goes right first and has to turn back
+        assert_eq!(search.order[1], Point { x: 1, y: 0 });
+        assert_eq!(search.order[2], Point { x: 0, y: 1 });
+        let path = search.get_path(&end).unwrap();
+        assert_eq!(search.get_distance(&end), Some(path.len() as u64 - 1));
+        assert_path(&path, start, end, &grid);
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        for graph in get_graphs() {
+            let search = super::dijkstra([0], |node| graph[node].clone());
+            let costs = get_costs(&graph);
+            assert_eq!(search.distances, costs);
+
+            for (node, cost) in costs {
+                let path = search.get_path(&node).unwrap();
+                let steps: u64 = path
+                    .windows(2)
+                    .map(|step| {
+                        graph[&step[0]]
+                            .iter()
+                            .find(|(to, _)| *to == step[1])
+                            .unwrap()
+                            .1
+                    })
+                    .sum();
+                assert_eq!((path[0], steps), (0, cost));
+            }
+        }
+    }
+
+    #[test]
+    fn test_astar() {
+        let grid = get_maze();
+        let start = grid.find(|c| *c == 'S').unwrap();
+        let end = grid.find(|c| *c == 'E').unwrap();
+        let neighbours = |point: &Point| -> Vec<(Point, u64)> {
+            get_open_neighbours(&grid, point)
+                .into_iter()
+                .map(|next| (next, 1))
+                .collect()
+        };
+        let manhattan = |point: &Point| point.x.abs_diff(end.x) + point.y.abs_diff(end.y);
+
+        let (cost, path) =
+            super::astar(start, |point| *point == end, neighbours, manhattan).unwrap();
+        assert_eq!(cost, 12);
+        assert_path(&path, start, end, &grid);
+
+        let walled = Point { x: 2, y: 0 };
+        assert_eq!(
+            super::astar(start, |point| *point == walled, neighbours, manhattan),
+            None
+        );
+
+        // without a heuristic it agrees with dijkstra on every graph and target
+        for graph in get_graphs() {
+            let costs = get_costs(&graph);
+            for target in graph.keys() {
+                let found =
+                    super::astar(0, |node| node == target, |node| graph[node].clone(), |_| 0);
+                assert_eq!(found.map(|(cost, _)| cost), costs.get(target).copied());
+            }
+        }
+    }
+
+    #[test]
+    fn test_network() {
+        // the nodes of day 8, walked with its instructions: a node and how far into them we are
+        let network: HashMap<&str, (&str, &str)> = HashMap::from([
+            ("AAA", ("BBB", "BBB")),
+            ("BBB", ("AAA", "ZZZ")),
+            ("ZZZ", ("ZZZ", "ZZZ")),
+        ]);
+        let instructions = ['L', 'L', 'R'];
+        let step = |(node, i): &(&'static str, usize)| {
+            let (left, right) = network[node];
+            let next = if instructions[*i] == 'L' { left } else { right };
+            [((next, (i + 1) % instructions.len()), 1)]
+        };
+
+        let (steps, path) =
+            super::astar(("AAA", 0), |(node, _)| *node == "ZZZ", step, |_| 0).unwrap();
+        assert_eq!(steps, 6);
+        assert_eq!(path.len(), 7);
+
+        let search = super::bfs([("AAA", 0)], |state| step(state).map(|(next, _)| next));
+        assert_eq!(search.get_distance(&("ZZZ", 0)), Some(6));
+    }
+}
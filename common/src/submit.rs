@@ -0,0 +1,626 @@
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::fetch::{FetchError, Fetcher};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Verdict {
+    Right,
+    Wrong,
+    TooHigh,
+    TooLow,
+    // seconds left until the next answer is accepted, if the server said so
+    Wait(Option<u64>),
+    AlreadySolved,
+    Unknown(String),
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Verdict::Right => write!(f, "right answer"),
+            Verdict::Wrong => write!(f, "wrong answer"),
+            Verdict::TooHigh => write!(f, "wrong answer, too high"),
+            Verdict::TooLow => write!(f, "wrong answer, too low"),
+            Verdict::Wait(Some(seconds)) => write!(f, "answered too recently, wait {}s", seconds),
+            Verdict::Wait(None) => write!(f, "answered too recently"),
+            Verdict::AlreadySolved => write!(f, "this part is already solved"),
+            Verdict::Unknown(text) => write!(f, "unexpected response: {}", text),
+        }
+    }
+}
+
+// One line of the history: when, which part, what was sent and what came back.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Attempt {
+    pub time: u64,
+    pub day: u32,
+    pub part: u32,
+    pub answer: String,
+    pub verdict: Verdict,
+    pub wait: Option<u64>, // seconds the server asked to wait before the next answer
+}
+
+// Every answer ever sent, kept in cache/<year>/submissions.tsv.
+#[derive(Debug, Default, PartialEq)]
+pub struct History {
+    pub attempts: Vec<Attempt>,
+}
+
+impl Fetcher {
+    pub fn get_history_path(&self) -> PathBuf {
+        self.cache
+            .join(self.year.to_string())
+            .join("submissions.tsv")
+    }
+
+    // Sends the answer unless the history already tells how it ends, and records the attempt.
+    pub fn submit(&self, day: u32, part: u32, answer: &str) -> Result<Verdict, FetchError> {
+        let path = self.get_history_path();
+        let mut history = History::read(&path)?;
+        history
+            .check(day, part, answer, get_now())
+            .map_err(FetchError::Refused)?;
+
+        let session = self.session.as_ref().ok_or(FetchError::NoSession)?;
+        let html = self.post_answer(day, part, answer, session)?;
+        let verdict = get_verdict(&html);
+
+        let attempt = Attempt {
+            time: get_now(),
+            day,
+            part,
+            answer: answer.to_string(),
+            verdict: verdict.clone(),
+            wait: get_wait(&html),
+        };
+        history.attempts.push(attempt.clone());
+        History::append(&path, &attempt)?;
+
+        Ok(verdict)
+    }
+
+    fn post_answer(
+        &self,
+        day: u32,
+        part: u32,
+        answer: &str,
+        session: &str,
+    ) -> Result<String, FetchError> {
+        let url = format!("{}/{}/day/{}/answer", self.url, self.year, day);
+        let agent = ureq::AgentBuilder::new()
+            .timeout(Duration::from_secs(30))
+            .build();
+
+        match agent
+            .post(&url)
+            .set("Cookie", &format!("session={}", session))
+            .set("User-Agent", "aoc runner (common::submit)")
+            .send_form(&[("level", &part.to_string()), ("answer", answer)])
+        {
+            Ok(response) => response
+                .into_string()
+                .map_err(|err| FetchError::Network(err.to_string())),
+            Err(ureq::Error::Status(status, response)) => Err(FetchError::Http {
+                status,
+                message: response
+                    .into_string()
+                    .unwrap_or_default()
+                    .trim()
+                    .to_string(),
+            }),
+            Err(err) => Err(FetchError::Network(err.to_string())),
+        }
+    }
+}
+
+impl History {
+    pub fn read(path: &Path) -> Result<History, FetchError> {
+        let text = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(err) => {
+                return Err(FetchError::Io {
+                    path: path.display().to_string(),
+                    message: err.to_string(),
+                })
+            }
+        };
+
+        let attempts = text
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| {
+                parse_attempt(line).ok_or_else(|| FetchError::Io {
+                    path: path.display().to_string(),
+                    message: format!("line {}: cannot read '{}'", i + 1, line),
+                })
+            })
+            .collect::<Result<Vec<Attempt>, FetchError>>()?;
+
+        Ok(History { attempts })
+    }
+
+    pub fn append(path: &Path, attempt: &Attempt) -> Result<(), FetchError> {
+        use std::io::Write;
+
+        let io_error = |err: std::io::Error| FetchError::Io {
+            path: path.display().to_string(),
+            message: err.to_string(),
+        };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(io_error)?;
+        }
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(io_error)?;
+        writeln!(file, "{}", format_attempt(attempt)).map_err(io_error)
+    }
+
+    // Why the answer must not be sent, if the earlier attempts already answer it.
+    pub fn check(&self, day: u32, part: u32, answer: &str, now: u64) -> Result<(), String> {
+        let attempts: Vec<&Attempt> = self
+            .attempts
+            .iter()
+            .filter(|attempt| attempt.day == day && attempt.part == part)
+            .collect();
+
+        if let Some(right) = attempts.iter().find(|a| a.verdict == Verdict::Right) {
+            return Err(format!(
+                "part {} is already solved with {}",
+                part, right.answer
+            ));
+        }
+        if let Some(known) = attempts.iter().find(|a| {
+            a.answer == answer
+                && matches!(
+                    a.verdict,
+                    Verdict::Wrong | Verdict::TooHigh | Verdict::TooLow
+                )
+        }) {
+            return Err(format!("{} was already sent: {}", answer, known.verdict));
+        }
+
+        if let Ok(value) = answer.parse::<i128>() {
+            for attempt in &attempts {
+                match (&attempt.verdict, attempt.answer.parse::<i128>()) {
+                    (Verdict::TooHigh, Ok(high)) if value >= high => {
+                        return Err(format!(
+                            "{} is not below {}, which was too high",
+                            value, high
+                        ))
+                    }
+                    (Verdict::TooLow, Ok(low)) if value <= low => {
+                        return Err(format!("{} is not above {}, which was too low", value, low))
+                    }
+                    _ => (),
+                }
+            }
+        }
+
+        // the server counts the wait per user, not per part
+        let until = self
+            .attempts
+            .iter()
+            .filter_map(|attempt| attempt.wait.map(|seconds| attempt.time + seconds))
+            .max();
+        match until {
+            Some(until) if until > now => Err(format!(
+                "wait {}s before sending another answer",
+                until - now
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn get_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|time| time.as_secs())
+        .unwrap_or_default()
+}
+
+// The answer page only has the verdict inside <article>, the rest is the site around it.
+pub fn get_verdict(html: &str) -> Verdict {
+    let text = get_article_text(html);
+
+    if text.contains("That's the right answer") {
+        Verdict::Right
+    } else if text.contains("That's not the right answer") {
+        if text.contains("too high") {
+            Verdict::TooHigh
+        } else if text.contains("too low") {
+            Verdict::TooLow
+        } else {
+            Verdict::Wrong
+        }
+    } else if text.contains("You gave an answer too recently") {
+        Verdict::Wait(get_wait_seconds(&text))
+    } else if text.contains("You don't seem to be solving the right level") {
+        Verdict::AlreadySolved
+    } else {
+        Verdict::Unknown(text)
+    }
+}
+
+// How long the server wants us to wait before the next answer, after a wrong one as well as
+// after one that was sent too soon.
+pub fn get_wait(html: &str) -> Option<u64> {
+    get_wait_seconds(&get_article_text(html))
+}
+
+fn get_article_text(html: &str) -> String {
+    let article = match (html.find("<article"), html.find("</article>")) {
+        (Some(start), Some(end)) if start < end => &html[start..end],
+        _ => html,
+    };
+
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in article.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            _ if !in_tag => text.push(c),
+            _ => (),
+        }
+    }
+    text.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+// "You have 1m 30s left to wait." -> 90, "Please wait one minute before trying again." -> 60
+fn get_wait_seconds(text: &str) -> Option<u64> {
+    // the lockout after repeated wrong answers says "..., please wait 5 minutes ..."
+    let text = text.to_lowercase();
+    if let Some((_, rest)) = text.split_once("please wait ") {
+        let (wait, _) = rest.split_once(" before trying again")?;
+        let (amount, unit) = wait.split_once(' ')?;
+        let value: u64 = match amount {
+            "one" => 1,
+            amount => amount.parse().ok()?,
+        };
+        return match unit.trim_end_matches('s') {
+            "hour" => Some(value * 3600),
+            "minute" => Some(value * 60),
+            "second" => Some(value),
+            _ => None,
+        };
+    }
+
+    // "you have to wait after submitting ..." comes first in the too recently message
+    let (rest, _) = text.split_once(" left to wait")?;
+    let (_, wait) = rest.rsplit_once("you have ")?;
+
+    wait.split_whitespace()
+        .map(|amount| {
+            let unit = amount.chars().last()?;
+            let value: u64 = amount[..amount.len() - 1].parse().ok()?;
+            match unit {
+                'h' => Some(value * 3600),
+                'm' => Some(value * 60),
+                's' => Some(value),
+                _ => None,
+            }
+        })
+        .sum()
+}
+
+fn format_verdict(verdict: &Verdict) -> String {
+    match verdict {
+        Verdict::Right => "right".to_string(),
+        Verdict::Wrong => "wrong".to_string(),
+        Verdict::TooHigh => "too-high".to_string(),
+        Verdict::TooLow => "too-low".to_string(),
+        Verdict::Wait(Some(seconds)) => format!("wait:{}", seconds),
+        Verdict::Wait(None) => "wait".to_string(),
+        Verdict::AlreadySolved => "already-solved".to_string(),
+        Verdict::Unknown(_) => "unknown".to_string(),
+    }
+}
+
+fn parse_verdict(text: &str) -> Option<Verdict> {
+    match text {
+        "right" => Some(Verdict::Right),
+        "wrong" => Some(Verdict::Wrong),
+        "too-high" => Some(Verdict::TooHigh),
+        "too-low" => Some(Verdict::TooLow),
+        "wait" => Some(Verdict::Wait(None)),
+        "already-solved" => Some(Verdict::AlreadySolved),
+        "unknown" => Some(Verdict::Unknown(String::new())),
+        _ => Some(Verdict::Wait(Some(
+            text.strip_prefix("wait:")?.parse().ok()?,
+        ))),
+    }
+}
+
+// time, day, part, answer, verdict and the seconds to wait (or -) separated by tabs
+fn format_attempt(attempt: &Attempt) -> String {
+    format!(
+        "{}\t{}\t{}\t{}\t{}\t{}",
+        attempt.time,
+        attempt.day,
+        attempt.part,
+        attempt.answer,
+        format_verdict(&attempt.verdict),
+        attempt
+            .wait
+            .map_or("-".to_string(), |seconds| seconds.to_string())
+    )
+}
+
+fn parse_attempt(line: &str) -> Option<Attempt> {
+    let fields: Vec<&str> = line.split('\t').collect();
+    let [time, day, part, answer, verdict, wait] = fields[..] else {
+        return None;
+    };
+    let wait = match wait {
+        "-" => None,
+        seconds => Some(seconds.parse().ok()?),
+    };
+
+    Some(Attempt {
+        time: time.parse().ok()?,
+        day: day.parse().ok()?,
+        part: part.parse().ok()?,
+        answer: answer.to_string(),
+        verdict: parse_verdict(verdict)?,
+        wait,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Verdict;
+    use std::path::PathBuf;
+    use std::sync::{Arc, Mutex};
+
+    // The request bodies the stand-in server got.
+    type Forms = Arc<Mutex<Vec<String>>>;
+
+    fn page(article: &str) -> String {
+        format!(
+            "<!DOCTYPE html><html><body><header>[Log Out]</header><main>\n<article><p>{}</p></article>\n</main></body></html>",
+            article
+        )
+    }
+
+    // Answers like the real site: 42 is right, lower and higher numbers are too low and too
+    // high, the second wrong answer in a row has to wait.
+    fn start_server() -> (String, Forms) {
+        let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", server.server_addr().to_ip().unwrap());
+        let forms: Forms = Arc::new(Mutex::new(Vec::new()));
+
+        let seen = forms.clone();
+        std::thread::spawn(move || {
+            for mut request in server.incoming_requests() {
+                let mut form = String::new();
+                let _ = request.as_reader().read_to_string(&mut form);
+                let answer = form
+                    .split('&')
+                    .find_map(|field| field.strip_prefix("answer="));
+                let article = match (answer, seen.lock().unwrap().len()) {
+                    (_, 2) => "You gave an answer too recently; you have to wait after submitting an answer before trying again.  You have 1m 5s left to wait. <a href=\"/2023/day/1\">[Return to Day 1]</a>",
+                    (Some("42"), _) => "That's the right answer!  You are <em>one gold star</em> closer to restoring snow operations.",
+                    (Some(answer), _) if answer.parse::<i32>().is_ok_and(|n| n > 42) => "That's not the right answer; your answer is too high.  If you're stuck, make sure you're using the full input data.  Please wait one minute before trying again.",
+                    (Some(answer), _) if answer.parse::<i32>().is_ok_and(|n| n < 42) => "That's not the right answer; your answer is too low.  Please wait one minute before trying again.",
+                    _ => "That's not the right answer.  If you're stuck, make sure you're using the full input data.",
+                };
+                seen.lock().unwrap().push(form);
+                let _ = request.respond(tiny_http::Response::from_string(page(article)));
+            }
+        });
+
+        (url, forms)
+    }
+
+    // Moves the whole history into the past, as if the waits had been sat out.
+    fn rewind(fetcher: &super::Fetcher, seconds: u64) {
+        let path = fetcher.get_history_path();
+        let mut history = super::History::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        for attempt in history.attempts.iter_mut() {
+            attempt.time -= seconds;
+            super::History::append(&path, attempt).unwrap();
+        }
+    }
+
+    fn get_fetcher(url: &str, name: &str) -> super::Fetcher {
+        let cache =
+            std::env::temp_dir().join(format!("aoc-submit-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&cache);
+        super::Fetcher {
+            url: url.to_string(),
+            year: 2023,
+            session: Some("secret".to_string()),
+            cache,
+        }
+    }
+
+    #[test]
+    fn test_submit() {
+        let (url, forms) = start_server();
+        let fetcher = get_fetcher(&url, "submit");
+
+        assert_eq!(fetcher.submit(1, 1, "50"), Ok(Verdict::TooHigh));
+
+        // a wrong answer has to wait a minute too
+        assert!(matches!(
+            fetcher.submit(1, 1, "30"),
+            Err(super::FetchError::Refused(_))
+        ));
+        let history = super::History::read(&fetcher.get_history_path()).unwrap();
+        let time = history.attempts[0].time;
+        assert_eq!(
+            history.check(1, 1, "30", time + 15),
+            Err("wait 45s before sending another answer".to_string())
+        );
+        assert_eq!(history.check(1, 1, "30", time + 60), Ok(()));
+        rewind(&fetcher, 60);
+
+        assert_eq!(fetcher.submit(1, 1, "30"), Ok(Verdict::TooLow));
+        rewind(&fetcher, 60);
+        assert_eq!(fetcher.submit(1, 1, "45"), Ok(Verdict::Wait(Some(65))));
+
+        // none of these reach the server
+        assert!(matches!(
+            fetcher.submit(1, 1, "45"),
+            Err(super::FetchError::Refused(_))
+        ));
+        let mut history = super::History::read(&fetcher.get_history_path()).unwrap();
+        history.attempts[2].time -= 65;
+        assert_eq!(
+            history.check(1, 1, "50", super::get_now()),
+            Err("50 was already sent: wrong answer, too high".to_string())
+        );
+        assert_eq!(
+            history.check(1, 1, "60", super::get_now()),
+            Err("60 is not below 50, which was too high".to_string())
+        );
+        assert_eq!(
+            history.check(1, 1, "12", super::get_now()),
+            Err("12 is not above 30, which was too low".to_string())
+        );
+        assert_eq!(history.check(1, 1, "45", super::get_now()), Ok(()));
+        assert_eq!(forms.lock().unwrap().len(), 3);
+
+        std::fs::remove_file(fetcher.get_history_path()).unwrap();
+        for attempt in &history.attempts {
+            super::History::append(&fetcher.get_history_path(), attempt).unwrap();
+        }
+        assert_eq!(fetcher.submit(1, 1, "42"), Ok(Verdict::Right));
+        assert_eq!(
+            fetcher.submit(1, 1, "43"),
+            Err(super::FetchError::Refused(
+                "part 1 is already solved with 42".to_string()
+            ))
+        );
+        assert_eq!(fetcher.submit(1, 2, "abc"), Ok(Verdict::Wrong));
+
+        assert_eq!(
+            forms.lock().unwrap()[..],
+            [
+                "level=1&answer=50",
+                "level=1&answer=30",
+                "level=1&answer=45",
+                "level=1&answer=42",
+                "level=2&answer=abc"
+            ]
+        );
+        assert_eq!(
+            super::History::read(&fetcher.get_history_path())
+                .unwrap()
+                .attempts
+                .iter()
+                .map(|attempt| (
+                    attempt.part,
+                    attempt.answer.as_str(),
+                    attempt.verdict.clone(),
+                    attempt.wait
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (1, "50", Verdict::TooHigh, Some(60)),
+                (1, "30", Verdict::TooLow, Some(60)),
+                (1, "45", Verdict::Wait(Some(65)), Some(65)),
+                (1, "42", Verdict::Right, None),
+                (2, "abc", Verdict::Wrong, None),
+            ]
+        );
+        std::fs::remove_dir_all(&fetcher.cache).unwrap();
+    }
+
+    #[test]
+    fn test_get_verdict() {
+        assert_eq!(
+            super::get_verdict(&page("You don't seem to be solving the right level.  Did you already complete it? <a href=\"/2023/day/1\">[Return to Day 1]</a>")),
+            Verdict::AlreadySolved
+        );
+        assert_eq!(
+            super::get_verdict(&page(
+                "You gave an answer too recently.  You have 34s left to wait."
+            )),
+            Verdict::Wait(Some(34))
+        );
+        assert_eq!(
+            super::get_verdict(&page("You gave an answer too recently.")),
+            Verdict::Wait(None)
+        );
+        assert_eq!(
+            super::get_verdict("<html><body>Please <b>log in</b>.</body></html>"),
+            Verdict::Unknown("Please log in.".to_string())
+        );
+    }
+
+    #[test]
+    fn test_get_wait() {
+        assert_eq!(
+            super::get_wait(&page(
+                "That's not the right answer.  Please wait one minute before trying again."
+            )),
+            Some(60)
+        );
+        assert_eq!(
+            super::get_wait(&page("That's not the right answer; your answer is too low.  Please wait 5 minutes before trying again.")),
+            Some(300)
+        );
+        assert_eq!(
+            super::get_wait(&page(
+                "You gave an answer too recently.  You have 1m 5s left to wait."
+            )),
+            Some(65)
+        );
+        assert_eq!(
+            super::get_wait(&page("That's not the right answer.  If you're stuck, make sure you're using the full input data; there are also some general tips on the <a href=\"/2023/about\">about page</a>, or you can ask for hints on the <a href=\"https://www.reddit.com/r/adventofcode/\" target=\"_blank\">subreddit</a>.  Because you have guessed incorrectly 4 times on this puzzle, please wait 5 minutes before trying again. <a href=\"/2023/day/1\">[Return to Day 1]</a>")),
+            Some(300)
+        );
+        assert_eq!(super::get_wait(&page("That's the right answer!")), None);
+    }
+
+    #[test]
+    fn test_history_lines() {
+        let path = PathBuf::from("history.tsv");
+        for verdict in [
+            Verdict::Right,
+            Verdict::Wrong,
+            Verdict::TooHigh,
+            Verdict::TooLow,
+            Verdict::Wait(Some(60)),
+            Verdict::Wait(None),
+            Verdict::AlreadySolved,
+        ] {
+            for wait in [None, Some(60)] {
+                let attempt = super::Attempt {
+                    time: 1701388800,
+                    day: 1,
+                    part: 2,
+                    answer: "54100".to_string(),
+                    verdict: verdict.clone(),
+                    wait,
+                };
+                assert_eq!(
+                    super::parse_attempt(&super::format_attempt(&attempt)),
+                    Some(attempt)
+                );
+            }
+        }
+        assert_eq!(super::parse_attempt("1701388800\t1\t2\t54100"), None);
+        assert_eq!(
+            super::parse_attempt("1701388800\t1\t2\t54100\twrong\tsoon"),
+            None
+        );
+        assert_eq!(
+            super::parse_attempt("1701388800\t1\t2\t54100\twait:30"),
+            None
+        );
+        assert_eq!(
+            super::History::read(&path.join("missing")),
+            Ok(super::History::default())
+        );
+    }
+}
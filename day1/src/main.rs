@@ -1,61 +1,290 @@
-use std::fs::read_to_string;
+use std::collections::{HashMap, VecDeque};
+use std::convert::Infallible;
+
+use common::input::InputFiles;
 
 fn main() {
-    let input1 = read_lines("./part1.txt");
+    let files = InputFiles::new(env!("CARGO_MANIFEST_DIR"), "input1.txt", "input2.txt");
 
-    // for line in input.iter() {
-    //     println!("{}", line);
-    // }
+    if common::bench::requested() {
+        bench(&files);
+        return;
+    }
 
-    println!("Part 1: {}", part1(input1));
+    // e.g. day1 --explain digits english zero teens german
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let paths = common::input::take_paths(&mut args).unwrap_or_else(|err| {
+        eprintln!("error: {}", err);
+        std::process::exit(1);
+    });
+    let explain = args.iter().any(|arg| arg == "--explain");
+    let names: Vec<&str> = args
+        .iter()
+        .filter(|arg| *arg != "--explain")
+        .map(String::as_str)
+        .collect();
 
-    let input2 = read_lines("./part2.txt");
+    let solved = files.run(&paths, |input1, input2| -> Result<(), Infallible> {
+        if explain {
+            println!(
+                "{}",
+                explain_calibration(&input1.lines, &get_scanner(&get_vocabulary(&["digits"])))
+            );
+        }
+        println!("Part 1: {}", part1(input1.lines.clone()));
 
-    println!("Part 2: {}", part2(input2));
+        if names.is_empty() && !explain {
+            println!("Part 2: {}", part2(input2.lines.clone()));
+        } else {
+            let names = if names.is_empty() {
+                vec!["digits", "english"]
+            } else {
+                names.clone()
+            };
+            let scanner = get_scanner(&get_vocabulary(&names));
+            if explain {
+                println!("{}", explain_calibration(&input2.lines, &scanner));
+            }
+            println!("Part 2: {}", calibration_sum(&input2.lines, &scanner));
+        }
+        Ok(())
+    });
+    if !solved {
+        std::process::exit(1);
+    }
 }
-fn read_lines(filename: &str) -> Vec<String> {
-    read_to_string(filename)
-        .unwrap() // panic on possible file-reading errors
-        .lines() // split the string into an iterator of string slices
-        .map(String::from) // make each slice into a string
-        .collect() // gather them together into a vector
+
+fn bench(files: &InputFiles) {
+    let (input1, input2) = files.read_lines();
+
+    print!(
+        "{}",
+        common::bench::Bench::new()
+            .run("read", || files.read_lines()) // the lines are all there is to parse
+            .run("part1", || part1(input1.clone()))
+            .run("part2", || part2(input2.clone()))
+            .report()
+    );
 }
 
 fn part1(input: Vec<String>) -> u32 {
-    let mut sum = 0;
-    for line in input {
-        sum += line
-            .chars()
-            .find(|c| c.is_ascii_digit())
-            .map_or(0, |d| d.to_digit(10).unwrap_or(0))
-            * 10;
-        sum += line
-            .chars()
-            .rev()
-            .find(|c| c.is_ascii_digit())
-            .map_or(0, |d| d.to_digit(10).unwrap_or(0));
+    calibration_sum(&input, &get_scanner(&get_vocabulary(&["digits"])))
+}
+
+fn part2(input: Vec<String>) -> u32 {
+    calibration_sum(
+        &input,
+        &get_scanner(&get_vocabulary(&["digits", "english"])),
+    )
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Token {
+    value: u32,
+    start: usize, // char positions, end exclusive
+    end: usize,
+}
+
+#[derive(Debug, Default)]
+struct Node {
+    next: HashMap<char, usize>,
+    fail: usize,
+    outputs: Vec<(usize, u32)>, // (length in chars, value) of every word ending here
+}
+
+// Aho-Corasick automaton over the vocabulary, finds all (also overlapping) tokens in one pass.
+#[derive(Debug)]
+struct Scanner {
+    nodes: Vec<Node>,
+}
+
+// Word lists are indexed by the value of the word, empty entries are skipped. The puzzle only
+// knows 1 to 9, zero has to be asked for.
+fn get_words(name: &str) -> Vec<&'static str> {
+    match name {
+        "digits" => vec!["", "1", "2", "3", "4", "5", "6", "7", "8", "9"],
+        "english" => vec![
+            "", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+        ],
+        "zero" => vec!["zero"],
+        "teens" => vec![
+            "",
+            "",
+            "",
+            "",
+            "",
+            "",
+            "",
+            "",
+            "",
+            "",
+            "ten",
+            "eleven",
+            "twelve",
+            "thirteen",
+            "fourteen",
+            "fifteen",
+            "sixteen",
+            "seventeen",
+            "eighteen",
+            "nineteen",
+            "twenty",
+        ],
+        "german" => vec![
+            "", "eins", "zwei", "drei", "vier", "fünf", "sechs", "sieben", "acht", "neun",
+        ],
+        _ => panic!("unknown vocabulary {}", name),
+    }
+}
+
+fn get_vocabulary(names: &[&str]) -> Vec<(String, u32)> {
+    let mut vocabulary: Vec<(String, u32)> = Vec::new();
+
+    for name in names {
+        for (value, word) in get_words(name).iter().enumerate() {
+            if !word.is_empty() {
+                vocabulary.push((word.to_string(), value as u32));
+            }
+        }
     }
-    sum
-}
-
-fn part2(mut input: Vec<String>) -> u32 {
-    for line in input.iter_mut() {
-        *line = line.replace("one", "o1e");
-        *line = line.replace("two", "t2o");
-        *line = line.replace("three", "t3e");
-        *line = line.replace("four", "f4r");
-        *line = line.replace("five", "f5e");
-        *line = line.replace("six", "s6x");
-        *line = line.replace("seven", "s7n");
-        *line = line.replace("eight", "e8t");
-        *line = line.replace("nine", "n9e");
+
+    vocabulary
+}
+
+fn get_scanner(vocabulary: &[(String, u32)]) -> Scanner {
+    let mut nodes: Vec<Node> = vec![Node::default()];
+
+    for (word, value) in vocabulary {
+        let mut current = 0;
+        for c in word.chars() {
+            current = match nodes[current].next.get(&c) {
+                Some(&next) => next,
+                None => {
+                    nodes.push(Node::default());
+                    let next = nodes.len() - 1;
+                    nodes[current].next.insert(c, next);
+                    next
+                }
+            };
+        }
+        nodes[current].outputs.push((word.chars().count(), *value));
+    }
+
+    // breadth first, so the fail link of the parent is always known
+    let mut queue: VecDeque<usize> = nodes[0].next.values().copied().collect();
+
+    while let Some(current) = queue.pop_front() {
+        let children: Vec<(char, usize)> = nodes[current]
+            .next
+            .iter()
+            .map(|(&c, &child)| (c, child))
+            .collect();
+
+        for (c, child) in children {
+            let mut fail = nodes[current].fail;
+            while fail != 0 && !nodes[fail].next.contains_key(&c) {
+                fail = nodes[fail].fail;
+            }
+            let fail = match nodes[fail].next.get(&c) {
+                Some(&next) if next != child => next,
+                _ => 0,
+            };
+
+            nodes[child].fail = fail;
+            let inherited = nodes[fail].outputs.clone();
+            nodes[child].outputs.extend(inherited);
+            queue.push_back(child);
+        }
+    }
+
+    Scanner { nodes }
+}
+
+fn scan_tokens(scanner: &Scanner, line: &str) -> Vec<Token> {
+    let mut tokens: Vec<Token> = Vec::new();
+    let mut current = 0;
+
+    for (i, c) in line.chars().enumerate() {
+        while current != 0 && !scanner.nodes[current].next.contains_key(&c) {
+            current = scanner.nodes[current].fail;
+        }
+        current = scanner.nodes[current].next.get(&c).copied().unwrap_or(0);
+
+        for &(len, value) in scanner.nodes[current].outputs.iter() {
+            tokens.push(Token {
+                value,
+                start: i + 1 - len,
+                end: i + 1,
+            });
+        }
+    }
+
+    tokens
+}
+
+// First is the token starting leftmost, last the one starting rightmost.
+// On equal starts the longer token wins ("eighteen" over "eight").
+fn get_first_and_last(scanner: &Scanner, line: &str) -> Option<(Token, Token)> {
+    let tokens = scan_tokens(scanner, line);
+
+    let first = tokens
+        .iter()
+        .min_by_key(|token| (token.start, std::cmp::Reverse(token.end)))?;
+    let last = tokens.iter().max_by_key(|token| (token.start, token.end))?;
+
+    Some((*first, *last))
+}
+
+// Lines without any token count as 0.
+fn get_calibration_value(scanner: &Scanner, line: &str) -> u32 {
+    get_first_and_last(scanner, line).map_or(0, |(first, last)| first.value * 10 + last.value)
+}
+
+fn calibration_sum(input: &[String], scanner: &Scanner) -> u32 {
+    input
+        .iter()
+        .map(|line| get_calibration_value(scanner, line))
+        .sum()
+}
+
+// One row per line with both tokens, their char positions and the value,
+// warnings for lines that contribute nothing and the total at the end.
+fn explain_calibration(input: &[String], scanner: &Scanner) -> String {
+    let mut rows: Vec<String> = Vec::new();
+    let mut warnings = 0;
+
+    for (i, line) in input.iter().enumerate() {
+        match get_first_and_last(scanner, line) {
+            Some((first, last)) => rows.push(format!(
+                "{:>5}: first {} at {}, last {} at {} -> {:>3}    {}",
+                i + 1,
+                first.value,
+                first.start,
+                last.value,
+                last.start,
+                get_calibration_value(scanner, line),
+                line
+            )),
+            None => {
+                warnings += 1;
+                rows.push(format!(
+                    "{:>5}: warning: no digit found -> {:>3}    {}",
+                    i + 1,
+                    0,
+                    line
+                ));
+            }
+        }
     }
 
-    // for line in input.iter() {
-    //     println!("{}", line);
-    // }
+    rows.push(format!(
+        "total: {} ({} lines, {} warnings)",
+        calibration_sum(input, scanner),
+        input.len(),
+        warnings
+    ));
 
-    part1(input)
+    rows.join("\n")
 }
 
 #[cfg(test)]
@@ -84,4 +313,101 @@ mod tests {
         ];
         assert_eq!(super::part2(input), 281);
     }
+
+    #[test]
+    fn test_scan_tokens() {
+        let scanner = super::get_scanner(&super::get_vocabulary(&["digits", "english"]));
+        let tokens = super::scan_tokens(&scanner, "eightwothree");
+
+        assert_eq!(
+            tokens,
+            vec![
+                super::Token {
+                    value: 8,
+                    start: 0,
+                    end: 5
+                },
+                super::Token {
+                    value: 2,
+                    start: 4,
+                    end: 7
+                },
+                super::Token {
+                    value: 3,
+                    start: 7,
+                    end: 12
+                },
+            ]
+        );
+        assert!(super::scan_tokens(&scanner, "abcdef").is_empty());
+    }
+
+    #[test]
+    fn test_get_first_and_last() {
+        let scanner = super::get_scanner(&super::get_vocabulary(&["digits", "english"]));
+
+        let (first, last) = super::get_first_and_last(&scanner, "zoneight").unwrap();
+        assert_eq!((first.value, last.value), (1, 8));
+
+        let (first, last) = super::get_first_and_last(&scanner, "7").unwrap();
+        assert_eq!((first.value, last.value), (7, 7));
+
+        let (first, last) = super::get_first_and_last(&scanner, "oneightwone").unwrap();
+        assert_eq!((first.value, last.value), (1, 1));
+
+        assert_eq!(super::get_first_and_last(&scanner, "xyz"), None);
+    }
+
+    #[test]
+    fn test_other_vocabularies() {
+        let scanner = super::get_scanner(&super::get_vocabulary(&["digits", "english", "teens"]));
+        let (first, last) = super::get_first_and_last(&scanner, "eighteen3twelve").unwrap();
+        assert_eq!((first.value, last.value), (18, 12));
+        let (first, last) = super::get_first_and_last(&scanner, "xseventeen").unwrap();
+        assert_eq!((first.value, last.value), (17, 17));
+
+        let scanner = super::get_scanner(&super::get_vocabulary(&["german"]));
+        let (first, last) = super::get_first_and_last(&scanner, "fünfzweiundsiebenacht").unwrap();
+        assert_eq!((first.value, last.value), (5, 8));
+        assert_eq!(
+            super::calibration_sum(&["dreiunddreißig".to_string()], &scanner),
+            33
+        );
+    }
+
+    #[test]
+    fn test_zero() {
+        let scanner = super::get_scanner(&super::get_vocabulary(&["digits", "english"]));
+        let (first, last) = super::get_first_and_last(&scanner, "zero5one0").unwrap();
+        assert_eq!((first.value, last.value), (5, 1));
+
+        let scanner = super::get_scanner(&super::get_vocabulary(&["digits", "english", "zero"]));
+        let (first, last) = super::get_first_and_last(&scanner, "zero5one0").unwrap();
+        assert_eq!((first.value, last.value), (0, 1));
+        assert_eq!(
+            super::calibration_sum(&["twozero".to_string()], &scanner),
+            20
+        );
+    }
+
+    #[test]
+    fn test_explain_calibration() {
+        let scanner = super::get_scanner(&super::get_vocabulary(&["digits"]));
+        let input: Vec<String> = vec![
+            "1abc2".to_string(),
+            "nothing".to_string(),
+            "treb7uchet".to_string(),
+        ];
+        let explanation = super::explain_calibration(&input, &scanner);
+        let rows: Vec<&str> = explanation.lines().collect();
+
+        assert_eq!(rows.len(), 4);
+        assert_eq!(rows[0], "    1: first 1 at 0, last 2 at 4 ->  12    1abc2");
+        assert_eq!(rows[1], "    2: warning: no digit found ->   0    nothing");
+        assert_eq!(
+            rows[2],
+            "    3: first 7 at 4, last 7 at 4 ->  77    treb7uchet"
+        );
+        assert_eq!(rows[3], "total: 89 (3 lines, 1 warnings)");
+    }
 }
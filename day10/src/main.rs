@@ -1,5 +1,9 @@
-use std::collections::HashMap;
-use std::fmt::Debug;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt::{Debug, Display};
+use std::io::IsTerminal;
+
+use common::input::{Input, InputFiles};
+use common::parse::ParseError;
 
 #[derive(Debug, Clone, Eq, Hash, PartialEq)]
 struct Point {
@@ -15,73 +19,243 @@ struct Pipe {
     looped: bool,
 }
 
+#[derive(Debug, PartialEq)]
+enum MazeError {
+    NoStart,
+    StartConnections {
+        start: Point,
+        connections: Vec<Point>,
+    },
+    DeadEnd {
+        at: Point,
+        from: Point,
+    },
+    Parse(ParseError),
+    Usage(&'static str),
+    Write {
+        path: String,
+        message: String,
+    },
+}
+
+impl Display for Point {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+impl Display for MazeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MazeError::NoStart => write!(f, "the maze has no start tile S"),
+            MazeError::StartConnections { start, connections } => write!(
+                f,
+                "start tile S at {} needs exactly 2 connecting pipes but has {}",
+                start,
+                connections.len()
+            ),
+            MazeError::DeadEnd { at, from } => write!(
+                f,
+                "the loop is broken at {}: it does not connect back to {}",
+                at, from
+            ),
+            MazeError::Parse(err) => write!(f, "{}", err),
+            MazeError::Usage(usage) => write!(f, "{}", usage),
+            MazeError::Write { path, message } => write!(f, "cannot write {}: {}", path, message),
+        }
+    }
+}
+
+impl From<ParseError> for MazeError {
+    fn from(err: ParseError) -> MazeError {
+        MazeError::Parse(err)
+    }
+}
+
+impl MazeError {
+    fn in_file(self, file: &str) -> MazeError {
+        match self {
+            MazeError::Parse(err) => MazeError::Parse(err.in_file(file)),
+            err => err,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Enclosure {
+    Pick,
+    Scanline,
+    FloodFill,
+}
+
+// Steps along the pipes from the start for every reachable tile, None for everything else.
+#[derive(Debug)]
+struct DistanceMap {
+    distances: Vec<Vec<Option<u64>>>,
+    farthest: Vec<Point>,
+    max: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Tile {
+    Loop(char),
+    Inside,
+    Outside,
+}
+
 fn main() {
-    println!("Part 1: {}", part1(common::read_lines("./input1.txt")));
-    println!("Part 2: {}", part2(common::read_lines("./input2.txt")));
+    let files = InputFiles::new(env!("CARGO_MANIFEST_DIR"), "input1.txt", "input2.txt");
+
+    if common::bench::requested() {
+        bench(&files);
+        return;
+    }
+
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let paths = common::input::take_paths(&mut args).unwrap_or_else(|err| {
+        eprintln!("error: {}", err);
+        std::process::exit(1);
+    });
+
+    let solved = files.run(&paths, |input1, input2| {
+        run(&args, input1, input2).map_err(|err| match err {
+            // already starts with "error:" and shows the line
+            MazeError::Parse(err) => err.to_string(),
+            err => format!("error: {}", err),
+        })
+    });
+    if !solved {
+        std::process::exit(1);
+    }
 }
 
-fn part1(input: Vec<String>) -> u64 {
-    let mut start_point = Point { x: 0, y: 0 };
-    let mut map = get_map(&input, &mut start_point);
-
-    map.insert(
-        Point {
-            x: start_point.x,
-            y: start_point.y,
-        },
-        get_start_pipe(&map, &start_point),
+fn bench(files: &InputFiles) {
+    let (input1, input2) = files.read_lines();
+
+    print!(
+        "{}",
+        common::bench::Bench::new()
+            .run("parse", || get_looped_map(&input1))
+            .run("part1", || part1(input1.clone()))
+            .run("part2", || part2(input2.clone()))
+            .report()
     );
+}
 
-    travel_loop(&mut map, &start_point) / 2
+fn run(args: &[String], input1: &Input, input2: &Input) -> Result<(), MazeError> {
+    let in_file = |input: &Input| {
+        let name = input.name.clone();
+        move |err: MazeError| err.in_file(&name)
+    };
+
+    match args.first().map(String::as_str) {
+        Some("render") => {
+            let input = &input2.lines;
+            match args.get(1).map(String::as_str) {
+                Some("--svg") => {
+                    let path = args
+                        .get(2)
+                        .ok_or(MazeError::Usage("usage: day10 render --svg <file>"))?;
+                    let svg = render_svg(input).map_err(in_file(input2))?;
+                    std::fs::write(path, svg).map_err(|err| MazeError::Write {
+                        path: path.clone(),
+                        message: err.to_string(),
+                    })?;
+                }
+                _ => println!(
+                    "{}",
+                    render(input, std::io::stdout().is_terminal()).map_err(in_file(input2))?
+                ),
+            }
+        }
+        Some("enclosed") => {
+            let method = match args.get(1).map(String::as_str) {
+                Some("scanline") => Enclosure::Scanline,
+                Some("flood") => Enclosure::FloodFill,
+                _ => Enclosure::Pick,
+            };
+            println!(
+                "{:?}: {}",
+                method,
+                count_enclosed(input2.lines.clone(), method).map_err(in_file(input2))?
+            );
+        }
+        Some("distances") => {
+            let distance_map = get_distance_map(&input1.lines).map_err(in_file(input1))?;
+            println!(
+                "{}",
+                render_heatmap(&distance_map, std::io::stdout().is_terminal())
+            );
+            for point in distance_map.farthest.iter() {
+                println!("Farthest: {} after {} steps", point, distance_map.max);
+            }
+        }
+        _ => {
+            println!(
+                "Part 1: {}",
+                part1(input1.lines.clone()).map_err(in_file(input1))?
+            );
+            println!(
+                "Part 2: {}",
+                part2(input2.lines.clone()).map_err(in_file(input2))?
+            );
+        }
+    }
+
+    Ok(())
 }
 
-#[allow(unused_variables)]
-fn part2(input: Vec<String>) -> u64 {
-    let mut start_point = Point { x: 0, y: 0 };
-    let mut map = get_map(&input, &mut start_point);
-
-    map.insert(
-        Point {
-            x: start_point.x,
-            y: start_point.y,
-        },
-        get_start_pipe(&map, &start_point),
-    );
+fn part1(input: Vec<String>) -> Result<u64, MazeError> {
+    let (_, loop_points) = get_looped_map(&input)?;
 
-    let outside_points = travel_loop(&mut map, &start_point);
+    Ok(loop_points.len() as u64 / 2)
+}
 
-    let mut previous = start_point;
-    let mut current = map.get_mut(&start_point).unwrap().connections[0];
+fn part2(input: Vec<String>) -> Result<u64, MazeError> {
+    let (_, loop_points) = get_looped_map(&input)?;
+    let outside_points = loop_points.len() as u64;
     let mut area = 0;
 
     //Shoelace formula
     //https://en.wikipedia.org/wiki/Shoelace_formula
-    for i in 0..outside_points {
-        let pipe = map.get_mut(&current).unwrap();
-        pipe.looped = true;
-        for connection in pipe.connections.iter() {
-            if connection != &previous {
-                area += previous.x * current.y - previous.y * current.x;
-                previous = current;
-                current = *connection;
-                break;
-            }
-        }
+    for (i, current) in loop_points.iter().enumerate() {
+        let next = loop_points[(i + 1) % loop_points.len()];
+        area += current.x * next.y - current.y * next.x;
     }
     area = area.abs() / 2;
 
     //Pick's theorem
     //https://en.wikipedia.org/wiki/Pick%27s_theorem
-    area as u64 + 1 - outside_points / 2
+    Ok(area as u64 + 1 - outside_points / 2)
 }
 
-fn travel_loop(map: &mut HashMap<Point, Pipe>, start: &Point) -> u64 {
-    let mut steps = 1;
+// Walks the loop once starting at start, marks every pipe on it as looped
+// and returns the visited points in order.
+// Fails on the first tile that does not lead back to where we came from.
+fn get_loop(map: &mut HashMap<Point, Pipe>, start: &Point) -> Result<Vec<Point>, MazeError> {
+    let mut points = vec![*start];
     let mut previous = *start;
-    let mut current = map.get_mut(start).unwrap().connections[0];
+    let mut current = match map.get(start).and_then(|pipe| pipe.connections.first()) {
+        Some(next) => *next,
+        None => {
+            return Err(MazeError::StartConnections {
+                start: *start,
+                connections: Vec::new(),
+            })
+        }
+    };
 
     while current != *start {
-        let pipe = map.get_mut(&current).unwrap();
+        points.push(current);
+        let pipe = match map.get_mut(&current) {
+            Some(pipe) if pipe.connections.contains(&previous) => pipe,
+            _ => {
+                return Err(MazeError::DeadEnd {
+                    at: current,
+                    from: previous,
+                })
+            }
+        };
         pipe.looped = true;
         for connection in pipe.connections.iter() {
             if connection != &previous {
@@ -90,14 +264,350 @@ fn travel_loop(map: &mut HashMap<Point, Pipe>, start: &Point) -> u64 {
                 break;
             }
         }
+    }
+
+    Ok(points)
+}
+
+// Breadth first search from start, only following pipes that are connected in both directions.
+fn get_distances(map: &HashMap<Point, Pipe>, start: &Point) -> HashMap<Point, u64> {
+    common::search::bfs([*start], |current| {
+        map.get(current)
+            .map(|pipe| pipe.connections.as_slice())
+            .unwrap_or_default()
+            .iter()
+            .filter(|next| {
+                map.get(next)
+                    .is_some_and(|pipe| pipe.connections.contains(current))
+            })
+            .copied()
+            .collect::<Vec<Point>>()
+    })
+    .distances
+}
+
+fn get_distance_map(input: &[String]) -> Result<DistanceMap, MazeError> {
+    let (map, loop_points) = get_looped_map(input)?;
+    let distances = get_distances(&map, &loop_points[0]);
+    let max = distances.values().copied().max().unwrap_or(0);
 
-        steps += 1;
+    let grid: Vec<Vec<Option<u64>>> = input
+        .iter()
+        .enumerate()
+        .map(|(y, line)| {
+            (0..line.chars().count())
+                .map(|x| {
+                    distances
+                        .get(&Point {
+                            x: x as i32,
+                            y: y as i32,
+                        })
+                        .copied()
+                })
+                .collect()
+        })
+        .collect();
+
+    let mut farthest: Vec<Point> = distances
+        .iter()
+        .filter(|(_, &distance)| distance == max)
+        .map(|(point, _)| *point)
+        .collect();
+    farthest.sort_by_key(|point| (point.y, point.x));
+
+    Ok(DistanceMap {
+        distances: grid,
+        farthest,
+        max,
+    })
+}
+
+// Distances scaled to 0-9, tiles that cannot be reached are shown as '.'.
+fn render_heatmap(distance_map: &DistanceMap, colour: bool) -> String {
+    const PALETTE: [u8; 10] = [21, 27, 33, 39, 45, 118, 190, 220, 208, 196];
+
+    distance_map
+        .distances
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|distance| match distance {
+                    Some(distance) => {
+                        let level = (distance * 9 / distance_map.max.max(1)) as usize;
+                        if colour {
+                            format!("\x1b[38;5;{}m{}\x1b[0m", PALETTE[level], level)
+                        } else {
+                            level.to_string()
+                        }
+                    }
+                    None => ".".to_string(),
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+fn get_pipe_char(point: &Point, pipe: &Pipe) -> char {
+    let north = pipe.connections.contains(&Point {
+        x: point.x,
+        y: point.y - 1,
+    });
+    let south = pipe.connections.contains(&Point {
+        x: point.x,
+        y: point.y + 1,
+    });
+    let east = pipe.connections.contains(&Point {
+        x: point.x + 1,
+        y: point.y,
+    });
+    let west = pipe.connections.contains(&Point {
+        x: point.x - 1,
+        y: point.y,
+    });
+
+    match (north, south, east, west) {
+        (true, true, _, _) => '┃',
+        (_, _, true, true) => '━',
+        (true, _, true, _) => '┗',
+        (true, _, _, true) => '┛',
+        (_, true, _, true) => '┓',
+        (_, true, true, _) => '┏',
+        _ => '·',
+    }
+}
+
+fn get_looped_map(input: &[String]) -> Result<(HashMap<Point, Pipe>, Vec<Point>), MazeError> {
+    if !input.iter().any(|line| line.contains('S')) {
+        return Err(MazeError::NoStart);
     }
 
-    steps
+    let mut start_point = Point { x: 0, y: 0 };
+    let mut map = get_map(input, &mut start_point)?;
+    let start_pipe = get_start_pipe(&map, &start_point)?;
+    map.insert(start_point, start_pipe);
+    let loop_points = get_loop(&mut map, &start_point)?;
+
+    Ok((map, loop_points))
 }
 
-fn get_map(input: &[String], start: &mut Point) -> HashMap<Point, Pipe> {
+fn count_enclosed(input: Vec<String>, method: Enclosure) -> Result<u64, MazeError> {
+    match method {
+        Enclosure::Pick => part2(input),
+        Enclosure::Scanline => {
+            let (map, _) = get_looped_map(&input)?;
+            Ok(get_enclosed_scanline(&input, &map).len() as u64)
+        }
+        Enclosure::FloodFill => {
+            let (map, _) = get_looped_map(&input)?;
+            Ok(get_enclosed_flood_fill(&input, &map).len() as u64)
+        }
+    }
+}
+
+// A tile is enclosed if an odd number of north-facing loop pipes lie to its left in the same row.
+fn get_enclosed_scanline(input: &[String], map: &HashMap<Point, Pipe>) -> HashSet<Point> {
+    let mut enclosed: HashSet<Point> = HashSet::new();
+
+    for (y, line) in input.iter().enumerate() {
+        let mut inside = false;
+
+        for x in 0..line.chars().count() {
+            let point = Point {
+                x: x as i32,
+                y: y as i32,
+            };
+            let pipe = map.get(&point).unwrap();
+
+            if pipe.looped {
+                if pipe.connections.contains(&Point {
+                    x: point.x,
+                    y: point.y - 1,
+                }) {
+                    inside = !inside;
+                }
+            } else if inside {
+                enclosed.insert(point);
+            }
+        }
+    }
+
+    enclosed
+}
+
+// Flood fills from the border on a grid with twice the resolution, so the water can squeeze
+// between two adjacent pipes that are not connected to each other.
+// Tile (x, y) becomes (2x + 1, 2y + 1), the cells in between are only blocked if the loop passes through.
+fn get_enclosed_flood_fill(input: &[String], map: &HashMap<Point, Pipe>) -> HashSet<Point> {
+    let height = input.len() as i32;
+    let width = input
+        .iter()
+        .map(|line| line.chars().count())
+        .max()
+        .unwrap_or(0) as i32;
+
+    let mut blocked: HashSet<Point> = HashSet::new();
+    for (point, pipe) in map.iter().filter(|(_, pipe)| pipe.looped) {
+        blocked.insert(Point {
+            x: 2 * point.x + 1,
+            y: 2 * point.y + 1,
+        });
+        for connection in pipe.connections.iter() {
+            blocked.insert(Point {
+                x: point.x + connection.x + 1,
+                y: point.y + connection.y + 1,
+            });
+        }
+    }
+
+    let mut water: HashSet<Point> = HashSet::new();
+    let mut queue: VecDeque<Point> = VecDeque::new();
+    queue.push_back(Point { x: 0, y: 0 });
+    water.insert(Point { x: 0, y: 0 });
+
+    while let Some(current) = queue.pop_front() {
+        for (dx, dy) in [(0, -1), (0, 1), (1, 0), (-1, 0)] {
+            let next = Point {
+                x: current.x + dx,
+                y: current.y + dy,
+            };
+            if next.x < 0 || next.y < 0 || next.x > 2 * width || next.y > 2 * height {
+                continue;
+            }
+            if blocked.contains(&next) || !water.insert(next) {
+                continue;
+            }
+            queue.push_back(next);
+        }
+    }
+
+    map.iter()
+        .filter(|(point, pipe)| {
+            !pipe.looped
+                && !water.contains(&Point {
+                    x: 2 * point.x + 1,
+                    y: 2 * point.y + 1,
+                })
+        })
+        .map(|(point, _)| *point)
+        .collect()
+}
+
+fn get_tiles(input: &[String]) -> Result<(Vec<Vec<Tile>>, Vec<Point>), MazeError> {
+    let (map, loop_points) = get_looped_map(input)?;
+    let enclosed = get_enclosed_scanline(input, &map);
+
+    let mut tiles: Vec<Vec<Tile>> = Vec::new();
+
+    for (y, line) in input.iter().enumerate() {
+        let mut row: Vec<Tile> = Vec::new();
+
+        for x in 0..line.chars().count() {
+            let point = Point {
+                x: x as i32,
+                y: y as i32,
+            };
+            let pipe = map.get(&point).unwrap();
+
+            if pipe.looped {
+                row.push(Tile::Loop(get_pipe_char(&point, pipe)));
+            } else if enclosed.contains(&point) {
+                row.push(Tile::Inside);
+            } else {
+                row.push(Tile::Outside);
+            }
+        }
+
+        tiles.push(row);
+    }
+
+    Ok((tiles, loop_points))
+}
+
+fn render(input: &[String], colour: bool) -> Result<String, MazeError> {
+    let (tiles, _) = get_tiles(input)?;
+
+    Ok(tiles
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|tile| {
+                    let (c, ansi) = match tile {
+                        Tile::Loop(c) => (*c, "\x1b[1;33m"),
+                        Tile::Inside => ('I', "\x1b[1;32m"),
+                        Tile::Outside => ('O', "\x1b[2;34m"),
+                    };
+                    if colour {
+                        format!("{}{}\x1b[0m", ansi, c)
+                    } else {
+                        c.to_string()
+                    }
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<String>>()
+        .join("\n"))
+}
+
+fn render_svg(input: &[String]) -> Result<String, MazeError> {
+    const CELL: i32 = 10;
+
+    let (tiles, loop_points) = get_tiles(input)?;
+    let width = tiles.iter().map(|row| row.len()).max().unwrap_or(0) as i32 * CELL;
+    let height = tiles.len() as i32 * CELL;
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\">\n",
+        width, height, width, height
+    );
+    svg += &format!(
+        "<rect width=\"{}\" height=\"{}\" fill=\"white\"/>\n",
+        width, height
+    );
+
+    for (y, row) in tiles.iter().enumerate() {
+        for (x, tile) in row.iter().enumerate() {
+            let fill = match tile {
+                Tile::Inside => "#4caf50",
+                Tile::Outside => "#cfd8dc",
+                Tile::Loop(_) => continue,
+            };
+            svg += &format!(
+                "<rect class=\"{}\" x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>\n",
+                if *tile == Tile::Inside { "I" } else { "O" },
+                x as i32 * CELL + 2,
+                y as i32 * CELL + 2,
+                CELL - 4,
+                CELL - 4,
+                fill
+            );
+        }
+    }
+
+    let path = loop_points
+        .iter()
+        .enumerate()
+        .map(|(i, point)| {
+            format!(
+                "{}{} {}",
+                if i == 0 { "M" } else { "L" },
+                point.x * CELL + CELL / 2,
+                point.y * CELL + CELL / 2
+            )
+        })
+        .collect::<Vec<String>>()
+        .join(" ");
+    svg += &format!(
+        "<path d=\"{} Z\" fill=\"none\" stroke=\"#f9a825\" stroke-width=\"{}\"/>\n",
+        path,
+        CELL / 3
+    );
+
+    svg += "</svg>\n";
+    Ok(svg)
+}
+
+fn get_map(input: &[String], start: &mut Point) -> Result<HashMap<Point, Pipe>, ParseError> {
     let mut map = HashMap::<Point, Pipe>::new();
     for (y, line) in input.iter().enumerate() {
         for (x, c) in line.chars().enumerate() {
@@ -170,7 +680,14 @@ fn get_map(input: &[String], start: &mut Point) -> HashMap<Point, Pipe> {
                     start.x = x as i32;
                     start.y = y as i32;
                 }
-                _ => {}
+                '.' => {}
+                _ => {
+                    let start = line.char_indices().nth(x).map_or(0, |(i, _)| i);
+                    let end = start + c.len_utf8();
+                    let expected = "a pipe (| - L J 7 F), '.' or 'S'";
+                    let err = common::parse::get_span_error(line, start, end, expected);
+                    return Err(err.at_line(y + 1));
+                }
             }
             map.insert(
                 Point {
@@ -182,35 +699,35 @@ fn get_map(input: &[String], start: &mut Point) -> HashMap<Point, Pipe> {
         }
     }
 
-    map
+    Ok(map)
 }
 
-fn get_start_pipe(map: &HashMap<Point, Pipe>, start: &Point) -> Pipe {
+fn get_start_pipe(map: &HashMap<Point, Pipe>, start: &Point) -> Result<Pipe, MazeError> {
     let mut start_pipe = Pipe {
         connections: Vec::new(),
         looped: true,
     };
 
-    for i in -1..=1 {
-        for j in -1..=1 {
-            if let Some(pipe) = map.get(&Point {
-                x: start.x + i,
-                y: start.y + j,
-            }) {
-                if pipe.connections.contains(&Point {
-                    x: start.x,
-                    y: start.y,
-                }) {
-                    start_pipe.connections.push(Point {
-                        x: start.x + i,
-                        y: start.y + j,
-                    });
-                }
+    for (i, j) in [(0, -1), (0, 1), (1, 0), (-1, 0)] {
+        let neighbour = Point {
+            x: start.x + i,
+            y: start.y + j,
+        };
+        if let Some(pipe) = map.get(&neighbour) {
+            if pipe.connections.contains(start) {
+                start_pipe.connections.push(neighbour);
             }
         }
     }
 
-    start_pipe
+    if start_pipe.connections.len() != 2 {
+        return Err(MazeError::StartConnections {
+            start: *start,
+            connections: start_pipe.connections,
+        });
+    }
+
+    Ok(start_pipe)
 }
 
 #[cfg(test)]
@@ -286,15 +803,15 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        assert_eq!(super::part1(get_test_input()), 4);
+        assert_eq!(super::part1(get_test_input()), Ok(4));
     }
 
     #[test]
     fn test_get_start() {
         let mut start_point = super::Point { x: 0, y: 0 };
-        let map = super::get_map(&get_test_input(), &mut start_point);
+        let map = super::get_map(&get_test_input(), &mut start_point).unwrap();
 
-        let start = super::get_start_pipe(&map, &start_point);
+        let start = super::get_start_pipe(&map, &start_point).unwrap();
 
         print!("{:?}", start);
 
@@ -306,7 +823,7 @@ mod tests {
     #[test]
     fn test_get_map() {
         let mut start_point = super::Point { x: 0, y: 0 };
-        let map = super::get_map(&get_test_input(), &mut start_point);
+        let map = super::get_map(&get_test_input(), &mut start_point).unwrap();
 
         print!("{:?}", map);
 
@@ -317,9 +834,238 @@ mod tests {
 
     #[test]
     fn test_part2() {
-        assert_eq!(super::part2(get_test_input_part2_1()), 4);
-        assert_eq!(super::part2(get_test_input_part2_2()), 4);
-        assert_eq!(super::part2(get_test_input_part2_3()), 8);
-        assert_eq!(super::part2(get_test_input_part2_4()), 10);
+        assert_eq!(super::part2(get_test_input_part2_1()), Ok(4));
+        assert_eq!(super::part2(get_test_input_part2_2()), Ok(4));
+        assert_eq!(super::part2(get_test_input_part2_3()), Ok(8));
+        assert_eq!(super::part2(get_test_input_part2_4()), Ok(10));
+    }
+
+    #[test]
+    fn test_get_loop() {
+        let mut start_point = super::Point { x: 0, y: 0 };
+        let mut map = super::get_map(&get_test_input(), &mut start_point).unwrap();
+        let start_pipe = super::get_start_pipe(&map, &start_point).unwrap();
+        map.insert(start_point, start_pipe);
+
+        let points = super::get_loop(&mut map, &start_point).unwrap();
+
+        assert_eq!(points.len(), 8);
+        assert_eq!(points[0], start_point);
+        assert!(points.iter().all(|point| map.get(point).unwrap().looped));
+    }
+
+    #[test]
+    fn test_render() {
+        let rendered = super::render(&get_test_input_part2_2(), false).unwrap();
+        let lines: Vec<&str> = rendered.lines().collect();
+
+        assert_eq!(lines.len(), 9);
+        assert_eq!(lines[0], "OOOOOOOOOO");
+        assert_eq!(lines[1], "O┏━━━━━━┓O");
+        assert_eq!(lines[6], "O┃II┃┃II┃O");
+        assert_eq!(rendered.matches('I').count(), 4);
+
+        let rendered = super::render(&get_test_input(), false).unwrap();
+        assert_eq!(rendered.lines().nth(1).unwrap(), "O┏━┓O");
+        assert_eq!(rendered.lines().nth(2).unwrap(), "O┃I┃O");
+    }
+
+    #[test]
+    fn test_render_colour() {
+        let rendered = super::render(&get_test_input(), true).unwrap();
+        assert!(rendered.starts_with("\x1b[2;34mO\x1b[0m"));
+        assert!(rendered.contains("\x1b[1;33m┏\x1b[0m"));
+    }
+
+    #[test]
+    fn test_render_svg() {
+        let svg = super::render_svg(&get_test_input_part2_3()).unwrap();
+
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.trim_end().ends_with("</svg>"));
+        assert_eq!(svg.matches("class=\"I\"").count(), 8);
+        assert_eq!(svg.matches("<path").count(), 1);
+    }
+
+    #[test]
+    fn test_render_svg_errors() {
+        let input = super::Input {
+            name: "input2.txt".to_string(),
+            lines: get_test_input_part2_3(),
+        };
+
+        assert_eq!(
+            super::run(&lines(&["render", "--svg"]), &input, &input),
+            Err(super::MazeError::Usage("usage: day10 render --svg <file>"))
+        );
+        let dir = std::env::temp_dir().join(format!("day10-missing-{}", std::process::id()));
+        let path = dir.join("maze.svg").display().to_string();
+        match super::run(&lines(&["render", "--svg", &path]), &input, &input) {
+            Err(err @ super::MazeError::Write { .. }) => {
+                assert!(err
+                    .to_string()
+                    .starts_with(&format!("cannot write {}: ", path)))
+            }
+            result => panic!("expected a write error, got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn test_get_enclosed_points() {
+        let (map, _) = super::get_looped_map(&get_test_input_part2_2()).unwrap();
+
+        let expected: std::collections::HashSet<super::Point> = [(2, 6), (3, 6), (6, 6), (7, 6)]
+            .iter()
+            .map(|&(x, y)| super::Point { x, y })
+            .collect();
+
+        assert_eq!(
+            super::get_enclosed_scanline(&get_test_input_part2_2(), &map),
+            expected
+        );
+        assert_eq!(
+            super::get_enclosed_flood_fill(&get_test_input_part2_2(), &map),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_enclosure_methods_agree() {
+        for (input, expected) in [
+            (get_test_input_part2_1(), 4),
+            (get_test_input_part2_2(), 4),
+            (get_test_input_part2_3(), 8),
+            (get_test_input_part2_4(), 10),
+        ] {
+            let (map, _) = super::get_looped_map(&input).unwrap();
+            let scanline = super::get_enclosed_scanline(&input, &map);
+            let flood_fill = super::get_enclosed_flood_fill(&input, &map);
+
+            assert_eq!(scanline, flood_fill);
+            for method in [
+                super::Enclosure::Pick,
+                super::Enclosure::Scanline,
+                super::Enclosure::FloodFill,
+            ] {
+                assert_eq!(super::count_enclosed(input.clone(), method), Ok(expected));
+            }
+        }
+    }
+
+    fn lines(input: &[&str]) -> Vec<String> {
+        input.iter().map(|line| line.to_string()).collect()
+    }
+
+    #[test]
+    fn test_get_start_pipe_errors() {
+        let start = super::Point { x: 1, y: 1 };
+
+        for (input, count) in [
+            (lines(&["...", ".S.", "..."]), 0),
+            (lines(&["...", ".S-", "..."]), 1),
+            (lines(&[".|.", "-S-", "..."]), 3),
+            (lines(&["F|7", "-S-", "L|J"]), 4),
+        ] {
+            let mut start_point = super::Point { x: 0, y: 0 };
+            let map = super::get_map(&input, &mut start_point).unwrap();
+            match super::get_start_pipe(&map, &start_point) {
+                Err(super::MazeError::StartConnections {
+                    start: s,
+                    connections,
+                }) => {
+                    assert_eq!(s, start);
+                    assert_eq!(connections.len(), count);
+                }
+                other => panic!("unexpected {:?}", other),
+            }
+        }
+
+        assert_eq!(
+            super::part1(lines(&["...", ".S-", "..."])),
+            Err(super::MazeError::StartConnections {
+                start,
+                connections: vec![super::Point { x: 2, y: 1 }],
+            })
+        );
+    }
+
+    #[test]
+    fn test_no_start() {
+        assert_eq!(
+            super::part1(lines(&["F7", "LJ"])),
+            Err(super::MazeError::NoStart)
+        );
+        assert!(super::render(&lines(&["F7", "LJ"]), false).is_err());
+    }
+
+    #[test]
+    fn test_unknown_tile() {
+        match super::part1(lines(&[".....", ".S-7.", ".|.|.", ".L-X.", "....."])) {
+            Err(super::MazeError::Parse(err)) => {
+                assert_eq!((err.line, err.column, err.width), (4, 4, 1));
+                assert_eq!(
+                    err.get_message(),
+                    "expected a pipe (| - L J 7 F), '.' or 'S', found 'X'"
+                );
+            }
+            result => panic!("expected a parse error, got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn test_dead_end() {
+        let err = super::part1(lines(&["S-7", "|.|", "L-."])).unwrap_err();
+        assert_eq!(
+            err,
+            super::MazeError::DeadEnd {
+                at: super::Point { x: 2, y: 2 },
+                from: super::Point { x: 1, y: 2 },
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "the loop is broken at (2, 2): it does not connect back to (1, 2)"
+        );
+
+        assert_eq!(
+            super::part2(lines(&["S-7", "|.|", "L--"])),
+            Err(super::MazeError::DeadEnd {
+                at: super::Point { x: 3, y: 2 },
+                from: super::Point { x: 2, y: 2 },
+            })
+        );
+    }
+
+    #[test]
+    fn test_get_distance_map() {
+        let distance_map = super::get_distance_map(&get_test_input()).unwrap();
+
+        assert_eq!(distance_map.max, 4);
+        assert_eq!(distance_map.farthest, vec![super::Point { x: 3, y: 3 }]);
+        assert_eq!(distance_map.distances[1][1], Some(0));
+        assert_eq!(distance_map.distances[1][3], Some(2));
+        assert_eq!(distance_map.distances[2][2], None);
+        assert_eq!(distance_map.distances[0][0], None);
+
+        for input in [
+            get_test_input_part2_1(),
+            get_test_input_part2_3(),
+            get_test_input_part2_4(),
+        ] {
+            let distance_map = super::get_distance_map(&input).unwrap();
+            assert_eq!(Ok(distance_map.max), super::part1(input));
+        }
+    }
+
+    #[test]
+    fn test_render_heatmap() {
+        let distance_map = super::get_distance_map(&get_test_input()).unwrap();
+        let heatmap = super::render_heatmap(&distance_map, false);
+
+        assert_eq!(
+            heatmap,
+            [".....", ".024.", ".2.6.", ".469.", "....."].join("\n")
+        );
+        assert!(super::render_heatmap(&distance_map, true).contains("\x1b[38;5;196m9\x1b[0m"));
     }
 }
@@ -1,29 +1,164 @@
+use std::collections::BTreeMap;
+
+use common::input::InputFiles;
+use common::parse::{get_span_error, ParseError};
+
+// Galaxies are numbered from 1 in reading order, like in the puzzle text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Pair {
+    a: usize,
+    b: usize,
+    distance: u64,
+}
+
 fn main() {
-    println!("Part 1: {}", part1(common::read_lines("./input1.txt")));
-    println!("Part 2: {}", part2(common::read_lines("./input2.txt")));
+    let files = InputFiles::new(env!("CARGO_MANIFEST_DIR"), "input1.txt", "input2.txt");
+
+    if common::bench::requested() {
+        bench(&files);
+        return;
+    }
+
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let paths = common::input::take_paths(&mut args).unwrap_or_else(|err| {
+        eprintln!("error: {}", err);
+        std::process::exit(1);
+    });
+
+    let solved = files.run(&paths, |input1, input2| -> Result<(), ParseError> {
+        if !args.is_empty() {
+            return query(&args, input1.lines.clone()).map_err(|err| err.in_file(&input1.name));
+        }
+
+        let part1 = part1(input1.lines.clone()).map_err(|err| err.in_file(&input1.name))?;
+        println!("Part 1: {}", part1);
+        let part2 = part2(input2.lines.clone()).map_err(|err| err.in_file(&input2.name))?;
+        println!("Part 2: {}", part2);
+        Ok(())
+    });
+    if !solved {
+        std::process::exit(1);
+    }
+}
+
+fn bench(files: &InputFiles) {
+    let (input1, input2) = files.read_lines();
+    let galaxies = get_expanded_galaxies(&input2, 1_000_000).unwrap();
+
+    print!(
+        "{}",
+        common::bench::Bench::new()
+            .run("parse", || get_expanded_galaxies(&input1, 2))
+            .run("part1", || part1(input1.clone()))
+            .run("part2", || part2(input2.clone()))
+            .run("pairwise sum", || manhattan_distance_sum(&galaxies))
+            .run("sorted sum", || fast_manhattan_distance_sum(&galaxies))
+            .report()
+    );
+}
+
+fn part1(input: Vec<String>) -> Result<u64, ParseError> {
+    get_distance_sum(input, 2)
 }
 
-fn part1(input: Vec<String>) -> u64 {
-    let mut galaxies = get_galaxies(&input);
-    let expanding_cols = get_expanding_cols(&input);
-    let expanding_rows = get_expanding_rows(&input);
-    expand_universe(&mut galaxies, &expanding_cols, &expanding_rows, 2);
-    fast_manhattan_distance_sum(&galaxies)
+fn part2(input: Vec<String>) -> Result<u64, ParseError> {
+    get_distance_sum(input, 1000000)
 }
 
-fn part2(input: Vec<String>) -> u64 {
-    let mut galaxies = get_galaxies(&input);
-    let expanding_cols = get_expanding_cols(&input);
-    let expanding_rows = get_expanding_rows(&input);
-    expand_universe(&mut galaxies, &expanding_cols, &expanding_rows, 1000000);
-    fast_manhattan_distance_sum(&galaxies)
+// the sum of the distances between all pairs with empty rows and columns expansion times as large
+fn get_distance_sum(input: Vec<String>, expansion: u64) -> Result<u64, ParseError> {
+    Ok(fast_manhattan_distance_sum(&get_expanded_galaxies(
+        &input, expansion,
+    )?))
 }
 
-fn get_galaxies(input: &[String]) -> Vec<common::Point> {
+// usage: day11 [--expansion N] pair A B | nearest | closest K | farthest K | histogram
+fn query(args: &[String], input: Vec<String>) -> Result<(), ParseError> {
+    let mut args = args.to_vec();
+    let mut expansion_factor = 2;
+
+    if let Some(i) = args.iter().position(|arg| arg == "--expansion") {
+        expansion_factor = args
+            .get(i + 1)
+            .and_then(|n| n.parse::<u64>().ok())
+            .expect("--expansion needs a number");
+        args.drain(i..=i + 1);
+    }
+
+    let galaxies = get_expanded_galaxies(&input, expansion_factor)?;
+    let number = |i: usize| -> usize {
+        args.get(i)
+            .and_then(|n| n.parse::<usize>().ok())
+            .unwrap_or_else(|| panic!("argument {} needs to be a number", i))
+    };
+
+    match args.first().map(String::as_str) {
+        Some("pair") => match galaxy_distance(&galaxies, number(1), number(2)) {
+            Some(distance) => println!("{}", distance),
+            None => println!("there are only {} galaxies", galaxies.len()),
+        },
+        Some("nearest") => {
+            for (i, pair) in nearest_neighbours(&galaxies).iter().enumerate() {
+                match pair {
+                    Some(pair) => println!("{} -> {}: {}", i + 1, pair.b, pair.distance),
+                    None => println!("{} -> none", i + 1),
+                }
+            }
+        }
+        Some("closest") => {
+            for pair in closest_pairs(&galaxies, number(1)) {
+                println!("{} - {}: {}", pair.a, pair.b, pair.distance);
+            }
+        }
+        Some("farthest") => {
+            for pair in farthest_pairs(&galaxies, number(1)) {
+                println!("{} - {}: {}", pair.a, pair.b, pair.distance);
+            }
+        }
+        Some("histogram") => {
+            for (distance, count) in distance_histogram(&galaxies) {
+                println!("{}: {}", distance, count);
+            }
+        }
+        _ => println!(
+            "usage: day11 [--expansion N] pair A B | nearest | closest K | farthest K | histogram"
+        ),
+    }
+
+    Ok(())
+}
+
+fn get_expanded_galaxies(
+    input: &[String],
+    expansion_factor: u64,
+) -> Result<Vec<common::Point>, ParseError> {
+    let mut galaxies = get_galaxies(input)?;
+    let expanding_cols = get_expanding_cols(input);
+    let expanding_rows = get_expanding_rows(input);
+    expand_universe(
+        &mut galaxies,
+        &expanding_cols,
+        &expanding_rows,
+        expansion_factor,
+    );
+    Ok(galaxies)
+}
+
+// The image has to be a rectangle of '.' and '#', as wide as its first line.
+fn get_galaxies(input: &[String]) -> Result<Vec<common::Point>, ParseError> {
     let mut galaxies: Vec<common::Point> = Vec::new();
+    let width = input.first().map_or(0, |line| line.chars().count());
 
     for (i, line) in input.iter().enumerate() {
-        for (j, c) in line.chars().enumerate() {
+        for (j, (offset, c)) in line.char_indices().enumerate() {
+            if j == width {
+                let expected = format!("{} tiles like the first line", width);
+                return Err(get_span_error(line, offset, line.len(), &expected).at_line(i + 1));
+            }
+            if c != '.' && c != '#' {
+                let end = offset + c.len_utf8();
+                return Err(get_span_error(line, offset, end, "'.' or '#'").at_line(i + 1));
+            }
             if c == '#' {
                 galaxies.push(common::Point {
                     x: j.try_into().unwrap(),
@@ -31,9 +166,13 @@ fn get_galaxies(input: &[String]) -> Vec<common::Point> {
                 });
             }
         }
+        if line.chars().count() < width {
+            let expected = format!("{} tiles like the first line", width);
+            return Err(get_span_error(line, line.len(), line.len(), &expected).at_line(i + 1));
+        }
     }
 
-    galaxies
+    Ok(galaxies)
 }
 
 fn get_expanding_rows(input: &[String]) -> Vec<u64> {
@@ -99,7 +238,7 @@ fn expand_universe(
 }
 
 #[allow(dead_code)] //fast_manhattan_distance_sum is used instead
-fn manhattan_distance_sum(points: &Vec<common::Point>) -> u64 {
+fn manhattan_distance_sum(points: &[common::Point]) -> u64 {
     let mut sum = 0;
 
     for i in 0..points.len() {
@@ -112,7 +251,7 @@ fn manhattan_distance_sum(points: &Vec<common::Point>) -> u64 {
     sum
 }
 
-fn fast_manhattan_distance_sum(points: &Vec<common::Point>) -> u64 {
+fn fast_manhattan_distance_sum(points: &[common::Point]) -> u64 {
     let mut sum = 0;
     let mut x_coords: Vec<i64> = points.iter().map(|point| point.x).collect::<Vec<_>>();
     let mut y_coords: Vec<i64> = points.iter().map(|point| point.y).collect::<Vec<_>>();
@@ -128,9 +267,85 @@ fn fast_manhattan_distance_sum(points: &Vec<common::Point>) -> u64 {
     sum.unsigned_abs()
 }
 
+fn manhattan_distance(a: &common::Point, b: &common::Point) -> u64 {
+    (a.x - b.x).unsigned_abs() + (a.y - b.y).unsigned_abs()
+}
+
+fn galaxy_distance(galaxies: &[common::Point], a: usize, b: usize) -> Option<u64> {
+    if a == 0 || b == 0 {
+        return None;
+    }
+    Some(manhattan_distance(
+        galaxies.get(a - 1)?,
+        galaxies.get(b - 1)?,
+    ))
+}
+
+fn get_pairs(galaxies: &[common::Point]) -> Vec<Pair> {
+    let mut pairs: Vec<Pair> = Vec::new();
+
+    for i in 0..galaxies.len() {
+        for j in i + 1..galaxies.len() {
+            pairs.push(Pair {
+                a: i + 1,
+                b: j + 1,
+                distance: manhattan_distance(&galaxies[i], &galaxies[j]),
+            });
+        }
+    }
+
+    pairs
+}
+
+// For every galaxy the closest other galaxy, seen from that galaxy (pair.a is always the galaxy itself).
+fn nearest_neighbours(galaxies: &[common::Point]) -> Vec<Option<Pair>> {
+    let mut nearest: Vec<Option<Pair>> = vec![None; galaxies.len()];
+
+    for pair in get_pairs(galaxies) {
+        for (from, to) in [(pair.a, pair.b), (pair.b, pair.a)] {
+            if nearest[from - 1].is_none_or(|n| pair.distance < n.distance) {
+                nearest[from - 1] = Some(Pair {
+                    a: from,
+                    b: to,
+                    distance: pair.distance,
+                });
+            }
+        }
+    }
+
+    nearest
+}
+
+fn closest_pairs(galaxies: &[common::Point], k: usize) -> Vec<Pair> {
+    let mut pairs = get_pairs(galaxies);
+    pairs.sort_by_key(|pair| (pair.distance, pair.a, pair.b));
+    pairs.truncate(k);
+    pairs
+}
+
+fn farthest_pairs(galaxies: &[common::Point], k: usize) -> Vec<Pair> {
+    let mut pairs = get_pairs(galaxies);
+    pairs.sort_by_key(|pair| (std::cmp::Reverse(pair.distance), pair.a, pair.b));
+    pairs.truncate(k);
+    pairs
+}
+
+fn distance_histogram(galaxies: &[common::Point]) -> BTreeMap<u64, u64> {
+    let mut histogram: BTreeMap<u64, u64> = BTreeMap::new();
+
+    for pair in get_pairs(galaxies) {
+        *histogram.entry(pair.distance).or_insert(0) += 1;
+    }
+
+    histogram
+}
+
 #[cfg(test)]
 mod tests {
+    include!(concat!(env!("OUT_DIR"), "/readme_examples.rs"));
 
+    // the rows of the puzzle example, not in its order: the numbers of the puzzle text hold for
+    // get_example_input(1)
     fn get_test_input() -> Vec<String> {
         vec![
             ".......#..".to_string(),
@@ -148,17 +363,24 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        assert_eq!(super::part1(get_test_input()), 374);
+        assert_eq!(super::part1(get_example_input(1)), Ok(374));
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(super::part2(get_test_input()), 82000210);
+        assert_eq!(super::part2(get_example_input(1)), Ok(82000210));
+    }
+
+    #[test]
+    fn test_get_distance_sum() {
+        // the smaller expansions of the puzzle text
+        assert_eq!(super::get_distance_sum(get_example_input(1), 10), Ok(1030));
+        assert_eq!(super::get_distance_sum(get_example_input(1), 100), Ok(8410));
     }
 
     #[test]
     fn test_get_expanding_rows() {
-        let rows = super::get_expanding_rows(&get_test_input());
+        let rows = super::get_expanding_rows(&get_example_input(1));
         assert_eq!(rows.len(), 2);
         assert_eq!(rows[0], 3);
         assert_eq!(rows[1], 7);
@@ -173,9 +395,42 @@ mod tests {
         assert_eq!(cols[2], 8);
     }
 
+    #[test]
+    fn test_get_galaxies_errors() {
+        let error = |row: usize, line: &str| {
+            let mut input = get_test_input();
+            input[row] = line.to_string();
+            let err = super::get_galaxies(&input).unwrap_err();
+            (err.line, err.column, err.width, err.get_message())
+        };
+
+        assert_eq!(
+            error(1, ".......#.o"),
+            (2, 10, 1, "expected '.' or '#', found 'o'".to_string())
+        );
+        assert_eq!(
+            error(2, "#..........."),
+            (
+                3,
+                11,
+                2,
+                "expected 10 tiles like the first line, found '..'".to_string()
+            )
+        );
+        assert_eq!(
+            error(3, "...."),
+            (
+                4,
+                5,
+                1,
+                "expected 10 tiles like the first line, found the end of the line".to_string()
+            )
+        );
+    }
+
     #[test]
     fn test_get_galaxies() {
-        let galaxies = super::get_galaxies(&get_test_input());
+        let galaxies = super::get_galaxies(&get_example_input(1)).unwrap();
         assert_eq!(galaxies.len(), 9);
         assert_eq!(galaxies[0], common::Point { x: 3, y: 0 });
         assert_eq!(galaxies[1], common::Point { x: 7, y: 1 });
@@ -185,9 +440,9 @@ mod tests {
 
     #[test]
     fn test_expand_universe() {
-        let mut galaxies = super::get_galaxies(&get_test_input());
-        let expanding_cols = super::get_expanding_cols(&get_test_input());
-        let expanding_rows = super::get_expanding_rows(&get_test_input());
+        let mut galaxies = super::get_galaxies(&get_example_input(1)).unwrap();
+        let expanding_cols = super::get_expanding_cols(&get_example_input(1));
+        let expanding_rows = super::get_expanding_rows(&get_example_input(1));
         super::expand_universe(&mut galaxies, &expanding_cols, &expanding_rows, 2);
         assert_eq!(galaxies.len(), 9);
         assert_eq!(galaxies[0], common::Point { x: 4, y: 0 });
@@ -198,19 +453,89 @@ mod tests {
 
     #[test]
     fn test_manhattan_distance_sum() {
-        let mut galaxies = super::get_galaxies(&get_test_input());
-        let expanding_cols = super::get_expanding_cols(&get_test_input());
-        let expanding_rows = super::get_expanding_rows(&get_test_input());
+        let mut galaxies = super::get_galaxies(&get_example_input(1)).unwrap();
+        let expanding_cols = super::get_expanding_cols(&get_example_input(1));
+        let expanding_rows = super::get_expanding_rows(&get_example_input(1));
         super::expand_universe(&mut galaxies, &expanding_cols, &expanding_rows, 2);
         assert_eq!(super::manhattan_distance_sum(&galaxies), 374);
     }
 
     #[test]
     fn test_fast_manhattan_distance_sum() {
-        let mut galaxies = super::get_galaxies(&get_test_input());
-        let expanding_cols = super::get_expanding_cols(&get_test_input());
-        let expanding_rows = super::get_expanding_rows(&get_test_input());
+        let mut galaxies = super::get_galaxies(&get_example_input(1)).unwrap();
+        let expanding_cols = super::get_expanding_cols(&get_example_input(1));
+        let expanding_rows = super::get_expanding_rows(&get_example_input(1));
         super::expand_universe(&mut galaxies, &expanding_cols, &expanding_rows, 2);
         assert_eq!(super::fast_manhattan_distance_sum(&galaxies), 374);
     }
+
+    #[test]
+    fn test_galaxy_distance() {
+        let galaxies = super::get_expanded_galaxies(&get_example_input(1), 2).unwrap();
+        assert_eq!(super::galaxy_distance(&galaxies, 5, 9), Some(9));
+        assert_eq!(super::galaxy_distance(&galaxies, 1, 7), Some(15));
+        assert_eq!(super::galaxy_distance(&galaxies, 3, 6), Some(17));
+        assert_eq!(super::galaxy_distance(&galaxies, 8, 9), Some(5));
+        assert_eq!(super::galaxy_distance(&galaxies, 9, 8), Some(5));
+        assert_eq!(super::galaxy_distance(&galaxies, 0, 1), None);
+        assert_eq!(super::galaxy_distance(&galaxies, 1, 10), None);
+    }
+
+    #[test]
+    fn test_nearest_neighbours() {
+        let galaxies = super::get_expanded_galaxies(&get_example_input(1), 2).unwrap();
+        let nearest = super::nearest_neighbours(&galaxies);
+        assert_eq!(nearest.len(), 9);
+        assert_eq!(
+            nearest[7],
+            Some(super::Pair {
+                a: 8,
+                b: 9,
+                distance: 5
+            })
+        );
+        assert_eq!(
+            nearest[8],
+            Some(super::Pair {
+                a: 9,
+                b: 7,
+                distance: 5
+            })
+        );
+        assert_eq!(super::nearest_neighbours(&galaxies[..1]), vec![None]);
+    }
+
+    #[test]
+    fn test_closest_and_farthest_pairs() {
+        let galaxies = super::get_expanded_galaxies(&get_example_input(1), 2).unwrap();
+
+        let closest = super::closest_pairs(&galaxies, 3);
+        assert_eq!(closest.len(), 3);
+        assert_eq!((closest[0].a, closest[0].b, closest[0].distance), (2, 4, 5));
+        assert_eq!((closest[1].a, closest[1].b, closest[1].distance), (3, 5, 5));
+        assert_eq!((closest[2].a, closest[2].b, closest[2].distance), (7, 9, 5));
+
+        let farthest = super::farthest_pairs(&galaxies, 100);
+        assert_eq!(farthest.len(), 36);
+        assert_eq!(
+            (farthest[0].a, farthest[0].b, farthest[0].distance),
+            (2, 8, 19)
+        );
+        assert!(farthest.windows(2).all(|w| w[0].distance >= w[1].distance));
+        assert_eq!(farthest.iter().map(|pair| pair.distance).sum::<u64>(), 374);
+    }
+
+    #[test]
+    fn test_distance_histogram() {
+        let galaxies = super::get_expanded_galaxies(&get_example_input(1), 2).unwrap();
+        let histogram = super::distance_histogram(&galaxies);
+        assert_eq!(histogram.values().sum::<u64>(), 36);
+        assert_eq!(
+            histogram
+                .iter()
+                .map(|(distance, count)| distance * count)
+                .sum::<u64>(),
+            374
+        );
+    }
 }
@@ -1,3 +1,5 @@
+use common::input::InputFiles;
+use common::parse::{get_span_error, ParseError, Pattern};
 use itertools::Itertools;
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -7,91 +9,128 @@ pub enum State {
     Unknown,
 }
 
+// the springs of one row and the sizes of its groups of broken springs
+type Record = (Vec<State>, Vec<u64>);
+
 fn main() {
-    println!("Part 1: {}", part1(common::read_lines("./input.txt")));
-    println!("Part 2: {}", part2(common::read_lines("./input.txt")));
+    let files = InputFiles::new(env!("CARGO_MANIFEST_DIR"), "input.txt", "input.txt");
+
+    if common::bench::requested() {
+        bench(&files);
+        return;
+    }
+
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let paths = common::input::take_paths(&mut args).unwrap_or_else(|err| {
+        eprintln!("error: {}", err);
+        std::process::exit(1);
+    });
+
+    let solved = files.run(&paths, |input1, input2| -> Result<(), ParseError> {
+        let part1 = part1(input1.lines.clone()).map_err(|err| err.in_file(&input1.name))?;
+        println!("Part 1: {}", part1);
+        let part2 = part2(input2.lines.clone()).map_err(|err| err.in_file(&input2.name))?;
+        println!("Part 2: {}", part2);
+        Ok(())
+    });
+    if !solved {
+        std::process::exit(1);
+    }
 }
 
-fn part1(input: Vec<String>) -> u64 {
-    input
-        .iter()
-        .map(|line| {
-            let (states, configs) = parse_input(line);
-            calculate_arrangements(&states, &configs)
-        })
-        .sum()
+fn bench(files: &InputFiles) {
+    let (input, _) = files.read_lines();
+
+    print!(
+        "{}",
+        common::bench::Bench::new()
+            .run("parse", || get_records(&input))
+            .run("part1", || part1(input.clone()))
+            .run("part2", || part2(input.clone()))
+            .report()
+    );
 }
 
-fn part2(input: Vec<String>) -> u64 {
-    input
+fn part1(input: Vec<String>) -> Result<u64, ParseError> {
+    Ok(get_records(&input)?
         .iter()
-        .map(|line| {
-            let (states, configs) = unfold_input(line);
-            calculate_arrangements(&states, &configs)
-        })
-        .sum()
+        .map(|(states, configs)| calculate_arrangements(states, configs))
+        .sum())
 }
 
-fn parse_input(line: &String) -> (Vec<State>, Vec<u64>) {
-    let mut states = Vec::new();
-    let mut configs = Vec::new();
-    let mut i = 0;
-    let split = line.split(" ").collect::<Vec<&str>>();
+fn part2(input: Vec<String>) -> Result<u64, ParseError> {
+    let pattern = Pattern::new("{} {}");
+
+    Ok(
+        common::parse::parse_lines(&input, |line| unfold_input(&pattern, line))?
+            .iter()
+            .map(|(states, configs)| calculate_arrangements(states, configs))
+            .sum(),
+    )
+}
+
+fn get_records(input: &[String]) -> Result<Vec<Record>, ParseError> {
+    let pattern = Pattern::new("{} {}");
+
+    common::parse::parse_lines(input, |line| parse_input(&pattern, line))
+}
 
-    split[0].chars().for_each(|c| {
+// "???.### 1,1,3"
+fn parse_input(pattern: &Pattern, line: &str) -> Result<Record, ParseError> {
+    let captures = pattern.captures(line)?;
+
+    let mut states = Vec::new();
+    for (i, c) in captures.get_str(0).char_indices() {
         states.push(match c {
             '#' => State::Broken,
             '.' => State::Ok,
             '?' => State::Unknown,
-            _ => panic!("Invalid state"),
+            _ => {
+                let expected = "a spring ('#', '.' or '?')";
+                return Err(get_span_error(line, i, i + c.len_utf8(), expected));
+            }
         });
-    });
+    }
 
-    split[1].split(",").for_each(|c| {
-        configs.push(c.parse::<u64>().unwrap());
-    });
+    let mut configs = Vec::new();
+    let mut start = line.len() - captures.get_str(1).len();
+    for config in captures.get_str(1).split(',') {
+        let end = start + config.len();
+        let size = config
+            .parse::<u64>()
+            .map_err(|_| get_span_error(line, start, end, "a group size"))?;
+        configs.push(size);
+        start = end + 1;
+    }
 
-    (states, configs)
+    Ok((states, configs))
 }
 
-fn unfold_input(line: &String) -> (Vec<State>, Vec<u64>) {
+// Five copies of the springs joined by '?' and five copies of the groups.
+fn unfold_input(pattern: &Pattern, line: &str) -> Result<Record, ParseError> {
+    let (folded_states, folded_configs) = parse_input(pattern, line)?;
     let mut states = Vec::new();
     let mut configs = Vec::new();
-    let mut i = 0;
-    let split = line.split(" ").collect::<Vec<&str>>();
+
     for i in 0..5 {
         if i != 0 {
             states.push(State::Unknown);
         }
-
-        split[0].chars().for_each(|c| {
-            states.push(match c {
-                '#' => State::Broken,
-                '.' => State::Ok,
-                '?' => State::Unknown,
-                _ => panic!("Invalid state"),
-            });
-        });
-
-        split[1].split(",").for_each(|c| {
-            configs.push(c.parse::<u64>().unwrap());
-        });
+        states.extend_from_slice(&folded_states);
+        configs.extend_from_slice(&folded_configs);
     }
 
-    (states, configs)
+    Ok((states, configs))
 }
 
-fn calculate_arrangements(states: &Vec<State>, configs: &Vec<u64>) -> u64 {
-    let mut count = 0;
-
+fn calculate_arrangements(states: &[State], configs: &[u64]) -> u64 {
     let total_spring_count = states.len();
-    let damaged_sequence_count = configs.len();
 
     let mut prev_counts = vec![0u64; total_spring_count + 2];
     prev_counts[total_spring_count + 1] = 1;
 
     // Consider each damaged-length from last to first.
-    for (d, damaged_len) in configs.iter().copied().enumerate().rev() {
+    for damaged_len in configs.iter().copied().rev() {
         let mut possibly_damaged_run_len = 0;
 
         let mut nways = 0;
@@ -154,7 +193,8 @@ fn calculate_arrangements(states: &Vec<State>, configs: &Vec<u64>) -> u64 {
     ans
 }
 
-fn test_arrangemnt(states: &Vec<State>, configs: &Vec<u64>) -> bool {
+#[cfg(test)]
+fn test_arrangemnt(states: &[State], configs: &[u64]) -> bool {
     let mut blocksize = 0;
     let mut config = 0;
     for state in states {
@@ -175,6 +215,11 @@ fn test_arrangemnt(states: &Vec<State>, configs: &Vec<u64>) -> bool {
 }
 
 #[cfg(test)]
+#[allow(
+    unused_variables,
+    clippy::bool_assert_comparison,
+    clippy::unnecessary_to_owned
+)]
 mod tests {
 
     fn get_test_input() -> Vec<String> {
@@ -190,94 +235,146 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        assert_eq!(super::part1(get_test_input()), 21);
+        assert_eq!(super::part1(get_test_input()), Ok(21));
     }
 
     #[test]
     fn test_calculate_arrangements() {
+        let pattern = super::Pattern::new("{} {}");
         {
-            let (states, configs) = super::parse_input(&"???.### 1,1,3".to_string());
+            let (states, configs) =
+                super::parse_input(&pattern, &"???.### 1,1,3".to_string()).unwrap();
             assert_eq!(super::calculate_arrangements(&states, &configs), 1);
         }
 
         {
-            let (states, configs) = super::parse_input(&".??..??...?##. 1,1,3".to_string());
+            let (states, configs) =
+                super::parse_input(&pattern, &".??..??...?##. 1,1,3".to_string()).unwrap();
             assert_eq!(super::calculate_arrangements(&states, &configs), 4);
         }
 
         {
-            let (states, configs) = super::parse_input(&"?#?#?#?#?#?#?#? 1,3,1,6".to_string());
+            let (states, configs) =
+                super::parse_input(&pattern, &"?#?#?#?#?#?#?#? 1,3,1,6".to_string()).unwrap();
             assert_eq!(super::calculate_arrangements(&states, &configs), 1);
         }
 
         {
-            let (states, configs) = super::parse_input(&"????.#...#... 4,1,1".to_string());
+            let (states, configs) =
+                super::parse_input(&pattern, &"????.#...#... 4,1,1".to_string()).unwrap();
             assert_eq!(super::calculate_arrangements(&states, &configs), 1);
         }
 
         {
-            let (states, configs) = super::parse_input(&"????.######..#####. 1,6,5".to_string());
+            let (states, configs) =
+                super::parse_input(&pattern, &"????.######..#####. 1,6,5".to_string()).unwrap();
             assert_eq!(super::calculate_arrangements(&states, &configs), 4);
         }
 
         {
-            let (states, configs) = super::parse_input(&"?###???????? 3,2,1".to_string());
+            let (states, configs) =
+                super::parse_input(&pattern, &"?###???????? 3,2,1".to_string()).unwrap();
             assert_eq!(super::calculate_arrangements(&states, &configs), 10);
         }
     }
 
     #[test]
     fn test_test_arrangement() {
+        let pattern = super::Pattern::new("{} {}");
         {
-            let (states, configs) = super::parse_input(&"#.#.### 1,1,3".to_string());
+            let (states, configs) =
+                super::parse_input(&pattern, &"#.#.### 1,1,3".to_string()).unwrap();
             assert_eq!(super::test_arrangemnt(&states, &configs), true);
         }
         {
-            let (states, configs) = super::parse_input(&"#.#.### 1,1,2".to_string());
+            let (states, configs) =
+                super::parse_input(&pattern, &"#.#.### 1,1,2".to_string()).unwrap();
             assert_eq!(super::test_arrangemnt(&states, &configs), false);
         }
     }
 
     #[test]
     fn test_unfold_input() {
-        let (unfold, config) = super::unfold_input(&"???.### 1,1,3".to_string());
+        let pattern = super::Pattern::new("{} {}");
+        let (unfold, config) = super::unfold_input(&pattern, &"???.### 1,1,3".to_string()).unwrap();
         assert_eq!(unfold.len(), 39);
     }
 
+    #[test]
+    fn test_parse_input_errors() {
+        let pattern = super::Pattern::new("{} {}");
+        let error = |line: &str| {
+            let err = super::parse_input(&pattern, line).unwrap_err();
+            (err.column, err.width, err.get_message())
+        };
+
+        assert_eq!(
+            error("??x.### 1,1,3"),
+            (
+                3,
+                1,
+                "expected a spring ('#', '.' or '?'), found 'x'".to_string()
+            )
+        );
+        assert_eq!(
+            error("???.### 1,a,3"),
+            (11, 1, "expected a group size, found 'a'".to_string())
+        );
+        assert_eq!(
+            error("???.### 1,,3"),
+            (
+                11,
+                1,
+                "expected a group size, found the end of the line".to_string()
+            )
+        );
+        assert_eq!(
+            error("???.###"),
+            (8, 1, "expected ' ', found the end of the line".to_string())
+        );
+    }
+
     #[test]
     fn test_part2() {
-        assert_eq!(super::part2(get_test_input()), 525152);
+        assert_eq!(super::part2(get_test_input()), Ok(525152));
     }
 
     #[test]
     fn test_calculate_arrangements_part2() {
+        let pattern = super::Pattern::new("{} {}");
         {
-            let (states, configs) = super::unfold_input(&"???.### 1,1,3".to_string());
+            let (states, configs) =
+                super::unfold_input(&pattern, &"???.### 1,1,3".to_string()).unwrap();
             assert_eq!(super::calculate_arrangements(&states, &configs), 1);
         }
 
         {
-            let (states, configs) = super::unfold_input(&".??..??...?##. 1,1,3".to_string());
+            let (states, configs) =
+                super::unfold_input(&pattern, &".??..??...?##. 1,1,3".to_string()).unwrap();
             assert_eq!(super::calculate_arrangements(&states, &configs), 16384);
         }
 
         {
-            let (states, configs) = super::unfold_input(&"?#?#?#?#?#?#?#? 1,3,1,6".to_string());
+            let (states, configs) =
+                super::unfold_input(&pattern, &"?#?#?#?#?#?#?#? 1,3,1,6".to_string()).unwrap();
             assert_eq!(super::calculate_arrangements(&states, &configs), 1);
         }
 
         {
-            let (states, configs) = super::unfold_input(&"????.#...#... 4,1,1".to_string());
+            let (states, configs) =
+                super::unfold_input(&pattern, &"????.#...#... 4,1,1".to_string()).unwrap();
             assert_eq!(super::calculate_arrangements(&states, &configs), 16);
         }
 
         {
-            let (states, configs) = super::unfold_input(&"????.######..#####. 1,6,5".to_string());
+            let (states, configs) =
+                super::unfold_input(&pattern, &"????.######..#####. 1,6,5".to_string()).unwrap();
             assert_eq!(super::calculate_arrangements(&states, &configs), 2500);
         }
 
         {
-            let (states, configs) = super::unfold_input(&"?###???????? 3,2,1".to_string());
+            let (states, configs) =
+                super::unfold_input(&pattern, &"?###???????? 3,2,1".to_string()).unwrap();
             assert_eq!(super::calculate_arrangements(&states, &configs), 506250);
         }
     }
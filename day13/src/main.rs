@@ -1,35 +1,107 @@
+use common::input::InputFiles;
+use common::parse::{get_span_error, ParseError};
+
 fn main() {
-    println!("Part 1: {}", part1(common::read_lines("./input.txt")));
-    println!("Part 2: {}", part2(common::read_lines("./input.txt")));
+    let files = InputFiles::new(env!("CARGO_MANIFEST_DIR"), "input.txt", "input.txt");
+
+    if common::bench::requested() {
+        bench(&files);
+        return;
+    }
+
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let paths = common::input::take_paths(&mut args).unwrap_or_else(|err| {
+        eprintln!("error: {}", err);
+        std::process::exit(1);
+    });
+
+    let solved = files.run(&paths, |input1, input2| -> Result<(), ParseError> {
+        let part1 = part1(input1.lines.clone()).map_err(|err| err.in_file(&input1.name))?;
+        println!("Part 1: {}", part1);
+        let part2 = part2(input2.lines.clone()).map_err(|err| err.in_file(&input2.name))?;
+        println!("Part 2: {}", part2);
+        Ok(())
+    });
+    if !solved {
+        std::process::exit(1);
+    }
 }
 
-fn part1(input: Vec<String>) -> u64 {
+fn bench(files: &InputFiles) {
+    let (input, _) = files.read_lines();
+
+    print!(
+        "{}",
+        common::bench::Bench::new()
+            .run("parse", || get_patterns(&input)
+                .map(|patterns| patterns.len()))
+            .run("part1", || part1(input.clone()))
+            .run("part2", || part2(input.clone()))
+            .report()
+    );
+}
+
+fn part1(input: Vec<String>) -> Result<u64, ParseError> {
     let mut result = 0;
 
-    input.split(|line| line.is_empty()).for_each(|group| {
+    for group in get_patterns(&input)? {
         result += find_symetry_horizontal(group, 0) * 100_u64;
         result += find_symetry_vertical(group, 0);
-    });
+    }
 
-    result
+    Ok(result)
 }
 
-fn part2(input: Vec<String>) -> u64 {
+fn part2(input: Vec<String>) -> Result<u64, ParseError> {
     let mut result = 0;
 
-    input.split(|line| line.is_empty()).for_each(|group| {
+    for group in get_patterns(&input)? {
         result += find_symetry_horizontal(group, 1) * 100_u64;
         result += find_symetry_vertical(group, 1);
-    });
+    }
+
+    Ok(result)
+}
+
+// The patterns between blank lines, each a rectangle of '.' and '#'.
+fn get_patterns(input: &[String]) -> Result<Vec<&[String]>, ParseError> {
+    let mut patterns = Vec::new();
+
+    for (first, group) in common::parse::get_blocks(input) {
+        let width = group[0].chars().count();
+        for (i, line) in group.iter().enumerate() {
+            let at_line = |err: ParseError| err.at_line(first + i + 1);
+            for (j, (offset, c)) in line.char_indices().enumerate() {
+                if j == width {
+                    let expected = format!("{} tiles like the pattern's first line", width);
+                    return Err(at_line(get_span_error(line, offset, line.len(), &expected)));
+                }
+                if c != '.' && c != '#' {
+                    let end = offset + c.len_utf8();
+                    return Err(at_line(get_span_error(line, offset, end, "'.' or '#'")));
+                }
+            }
+            if line.chars().count() < width {
+                let expected = format!("{} tiles like the pattern's first line", width);
+                return Err(at_line(get_span_error(
+                    line,
+                    line.len(),
+                    line.len(),
+                    &expected,
+                )));
+            }
+        }
+        patterns.push(group);
+    }
 
-    result
+    Ok(patterns)
 }
 
 fn find_symetry_horizontal(group: &[String], allowed_smudges: u64) -> u64 {
-    for (i, line) in group.iter().enumerate() {
+    for i in 0..group.len() {
         if i < group.len() - 1 {
             if check_symetry_horizontal(group, i) == allowed_smudges {
-                return i as u64 + 1 as u64;
+                return i as u64 + 1_u64;
             } else {
                 continue;
             }
@@ -44,7 +116,8 @@ fn check_symetry_horizontal(group: &[String], i: usize) -> u64 {
     for j in 0..group.len() / 2 {
         if (i as i64 - j as i64) >= 0 && (i + j + 1) < group.len() {
             for k in 0..group[0].len() {
-                if group[i - j].chars().nth(k).unwrap() != group[i + j + 1].chars().nth(k).unwrap() {
+                if group[i - j].chars().nth(k).unwrap() != group[i + j + 1].chars().nth(k).unwrap()
+                {
                     diff += 1;
                 }
             }
@@ -56,7 +129,7 @@ fn check_symetry_horizontal(group: &[String], i: usize) -> u64 {
 fn find_symetry_vertical(group: &[String], allowed_smudges: u64) -> u64 {
     for i in 0..(group[0].len() - 1) {
         if check_symetry_vertically(group, i) == allowed_smudges {
-            return i as u64 + 1 as u64;
+            return i as u64 + 1_u64;
         }
     }
     0
@@ -66,10 +139,8 @@ fn check_symetry_vertically(group: &[String], i: usize) -> u64 {
     let mut diff = 0_u64;
     for j in 0..group[0].len() / 2 {
         if (i as i64 - j as i64) >= 0 && (i + j + 1) < group[0].len() {
-            for line in 0..group.len() {
-                if group[line].chars().nth(i - j).unwrap()
-                    != group[line].chars().nth(i + j + 1).unwrap()
-                {
+            for line in group {
+                if line.chars().nth(i - j).unwrap() != line.chars().nth(i + j + 1).unwrap() {
                     diff += 1;
                 }
             }
@@ -81,24 +152,9 @@ fn check_symetry_vertically(group: &[String], i: usize) -> u64 {
 
 #[cfg(test)]
 mod tests {
+    include!(concat!(env!("OUT_DIR"), "/readme_examples.rs"));
     fn get_test_input() -> Vec<String> {
-        vec![
-            "#.##..##.".to_string(),
-            "..#.##.#.".to_string(),
-            "##......#".to_string(),
-            "##......#".to_string(),
-            "..#.##.#.".to_string(),
-            "..##..##.".to_string(),
-            "#.#.##.#.".to_string(),
-            "".to_string(),
-            "#...##..#".to_string(),
-            "#....#..#".to_string(),
-            "..##..###".to_string(),
-            "#####.##.".to_string(),
-            "#####.##.".to_string(),
-            "..##..###".to_string(),
-            "#....#..#".to_string(),
-        ]
+        get_example_input(1)
     }
 
     fn get_test_group1() -> Vec<String> {
@@ -127,12 +183,31 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        assert_eq!(super::part1(get_test_input()), 405);
+        assert_eq!(super::part1(get_test_input()), Ok(405));
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(super::part2(get_test_input()), 400);
+        assert_eq!(super::part2(get_test_input()), Ok(400));
+    }
+
+    #[test]
+    fn test_get_patterns() {
+        let mut input = get_test_input();
+        assert_eq!(super::get_patterns(&input).unwrap().len(), 2);
+
+        input[9] = "#...##..#.".to_string();
+        let err = super::get_patterns(&input).unwrap_err();
+        assert_eq!((err.line, err.column, err.width), (10, 10, 1));
+        assert_eq!(
+            err.get_message(),
+            "expected 9 tiles like the pattern's first line, found '.'"
+        );
+
+        input[9] = "#...#o..#".to_string();
+        let err = super::get_patterns(&input).unwrap_err();
+        assert_eq!((err.line, err.column), (10, 6));
+        assert_eq!(err.get_message(), "expected '.' or '#', found 'o'");
     }
 
     #[test]
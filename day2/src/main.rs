@@ -1,158 +1,430 @@
-use std::fs::read_to_string;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::str::FromStr;
+
+use common::input::InputFiles;
+use common::parse::{get_error, ParseError};
+
+const INPUT: &str = "input.txt";
 
 fn main() {
-    let input = read_lines("./input.txt");
+    let files = InputFiles::new(env!("CARGO_MANIFEST_DIR"), INPUT, INPUT);
+
+    if common::bench::requested() {
+        bench(&files);
+        return;
+    }
+
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let paths = common::input::take_paths(&mut args).unwrap_or_else(|err| {
+        eprintln!("error: {}", err);
+        std::process::exit(1);
+    });
+    let report = args.iter().any(|arg| arg == "--report");
+    args.retain(|arg| arg != "--report");
+
+    let bags = match get_bags(&args) {
+        Ok(bags) => bags,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            std::process::exit(1);
+        }
+    };
+
+    let solved = files.run(&paths, |input, _| -> Result<(), ParseError> {
+        let in_file = |err: ParseError| err.in_file(&input.name);
+        if report {
+            let games = get_games(&input.lines).map_err(in_file)?;
+            println!("{}", get_report(&games, &bags));
+            return Ok(());
+        }
 
-    println!("Part 1: {}", part1(input.clone()));
-    println!("Part 2: {}", part2(input));
+        let part1 = part1(&input.lines, &bags[0]).map_err(in_file)?;
+        println!("Part 1: {}", part1);
+        let part2 = part2(&input.lines).map_err(in_file)?;
+        println!("Part 2: {}", part2);
+        Ok(())
+    });
+    if !solved {
+        std::process::exit(1);
+    }
+}
 
-    //println!("Part 2: {}", part2(input));
+fn bench(files: &InputFiles) {
+    let (input, _) = files.read_lines();
+    let bag = get_default_bag();
+
+    print!(
+        "{}",
+        common::bench::Bench::new()
+            .run("parse", || get_games(&input))
+            .run("part1", || part1(&input, &bag))
+            .run("part2", || part2(&input))
+            .report()
+    );
 }
 
-#[derive(Debug)]
-struct Cube<'a> {
-    color: &'a str,
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+struct Colour(String);
+
+impl From<&str> for Colour {
+    fn from(name: &str) -> Self {
+        Colour(name.to_string())
+    }
+}
+
+impl Display for Colour {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+// How many cubes of each colour are in the bag, colours that are not listed are not in the bag at all.
+type Bag = HashMap<Colour, u32>;
+
+#[derive(Debug, Clone, PartialEq)]
+struct Cube {
+    colour: Colour,
     num: u32,
 }
 
-#[derive(Debug)]
-struct Set<'a> {
-    cubes: Vec<Cube<'a>>,
+#[derive(Debug, Clone, PartialEq)]
+struct Set {
+    cubes: Vec<Cube>,
 }
 
-#[derive(Debug)]
-struct Game<'a> {
+#[derive(Debug, Clone, PartialEq)]
+struct Game {
     game_num: u32,
-    game: Vec<Set<'a>>,
+    game: Vec<Set>,
 }
 
-fn read_lines(filename: &str) -> Vec<String> {
-    read_to_string(filename)
-        .unwrap() // panic on possible file-reading errors
-        .lines() // split the string into an iterator of string slices
-        .map(String::from) // make each slice into a string
-        .collect() // gather them together into a vector
+fn part1(input: &[String], bag: &Bag) -> Result<u32, ParseError> {
+    Ok(get_possible_sum(&get_games(input)?, bag))
 }
 
-fn part1(input: Vec<String>) -> u32 {
-    let mut sum: u32 = 0;
+fn part2(input: &[String]) -> Result<u32, ParseError> {
+    Ok(get_power_sum(&get_games(input)?))
+}
 
-    input.iter().for_each(|line: &String| {
-        let game: Game = get_game(line);
-        if is_valid(&game) {
-            sum += game.game_num;
+fn get_possible_sum(games: &[Game], bag: &Bag) -> u32 {
+    get_possible_games(games, bag).iter().sum()
+}
+
+fn get_power_sum(games: &[Game]) -> u32 {
+    games.iter().map(|game| power(&get_min_set(game))).sum()
+}
+
+fn get_default_bag() -> Bag {
+    let mut bag = Bag::new();
+    bag.insert(Colour::from("red"), 12);
+    bag.insert(Colour::from("green"), 13);
+    bag.insert(Colour::from("blue"), 14);
+    bag
+}
+
+// --bag red=12,green=13,blue=14 or --bag-file <file> with one "colour count" per line,
+// both can be given several times. Without any bag the one from the puzzle is used.
+fn get_bags(args: &[String]) -> Result<Vec<Bag>, String> {
+    let mut bags: Vec<Bag> = Vec::new();
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match (arg.as_str(), args.next()) {
+            ("--bag", Some(limits)) => bags.push(parse_bag(limits.split(','))?),
+            ("--bag-file", Some(path)) => {
+                let content = std::fs::read_to_string(path).map_err(|err| err.to_string())?;
+                bags.push(parse_bag(
+                    content.lines().filter(|line| !line.trim().is_empty()),
+                )?);
+            }
+            _ => {
+                return Err(format!(
+                    "unknown argument {}, use --bag red=12,green=13 or --bag-file <file>",
+                    arg
+                ))
+            }
         }
-    });
+    }
+
+    if bags.is_empty() {
+        bags.push(get_default_bag());
+    }
 
-    sum
+    Ok(bags)
 }
 
-fn part2(input: Vec<String>) -> u32 {
-    let mut sum: u32 = 0;
+fn parse_bag<'a>(limits: impl Iterator<Item = &'a str>) -> Result<Bag, String> {
+    let mut bag = Bag::new();
+
+    for limit in limits {
+        let mut split = limit
+            .split(|c: char| c == '=' || c == ':' || c.is_whitespace())
+            .filter(|s| !s.is_empty());
+
+        match (
+            split.next(),
+            split.next().map(str::parse::<u32>),
+            split.next(),
+        ) {
+            (Some(colour), Some(Ok(num)), None) => {
+                bag.insert(Colour::from(colour), num);
+            }
+            _ => return Err(format!("invalid bag limit '{}'", limit.trim())),
+        }
+    }
 
-    input.iter().for_each(|line: &String| {
-        let game: Game = get_game(line);
-        sum += power(&get_min_set(&game));
-    });
+    Ok(bag)
+}
 
-    sum
+fn get_games(input: &[String]) -> Result<Vec<Game>, ParseError> {
+    common::parse::parse_lines(input, get_game)
 }
 
 // Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
-fn get_game(line: &str) -> Game {
-    let mut game: Game = Game {
-        game_num: get_game_number(line),
-        game: vec![],
-    };
-
-    line.split(':').collect::<Vec<&str>>()[1]
-        .split(';')
-        .collect::<Vec<&str>>()
-        .iter()
-        .for_each(|s| {
-            game.game.push(get_set(s));
-        });
-    game
+fn get_game(line: &str) -> Result<Game, ParseError> {
+    line.parse::<Game>()
 }
 
-fn is_valid(game: &Game) -> bool {
-    let mut valid: bool = true;
+impl FromStr for Game {
+    type Err = ParseError;
 
-    game.game.iter().for_each(|set: &Set| {
-        set.cubes.iter().for_each(|cube: &Cube| {
-            if cube.color == "red" && cube.num > 12 {
-                valid = false;
-            }
-            if cube.color == "green" && cube.num > 13 {
-                valid = false;
-            }
-            if cube.color == "blue" && cube.num > 14 {
-                valid = false;
-            }
-        });
-    });
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let colon = line
+            .find(':')
+            .ok_or_else(|| get_error(line, line.len(), "':'"))?;
+
+        let mut game: Game = Game {
+            game_num: get_game_number(&line[..colon])?,
+            game: vec![],
+        };
+
+        let mut start = colon + 1;
+        for set in line[colon + 1..].split(';') {
+            game.game.push(get_set(line, start, start + set.len())?);
+            start += set.len() + 1;
+        }
 
-    valid
+        Ok(game)
+    }
+}
+
+fn is_valid(game: &Game, bag: &Bag) -> bool {
+    game.game.iter().all(|set| {
+        set.cubes
+            .iter()
+            .all(|cube| cube.num <= bag.get(&cube.colour).copied().unwrap_or(0))
+    })
 }
 
 // 3 blue, 4 red
-fn get_set(line: &str) -> Set {
+// The set is text[start..end], errors point into the whole text.
+fn get_set(text: &str, start: usize, end: usize) -> Result<Set, ParseError> {
     let mut set: Set = Set { cubes: vec![] };
 
-    line.split(',').collect::<Vec<&str>>().iter().for_each(|s| {
-        set.cubes.push(get_cube(s));
-    });
-    set
+    let mut position = start;
+    for cube in text[start..end].split(',') {
+        set.cubes
+            .push(get_cube(text, position, position + cube.len())?);
+        position += cube.len() + 1;
+    }
+
+    Ok(set)
 }
 
-// 3 blue
-fn get_cube(line: &str) -> Cube {
-    let split: Vec<&str> = line.split(' ').collect::<Vec<&str>>();
+// 3 blue, the cube is text[offset..end]
+fn get_cube(text: &str, offset: usize, end: usize) -> Result<Cube, ParseError> {
+    let line = &text[offset..end];
+    let error = |i: usize, expected: &str| get_error(text, offset + i, expected);
 
-    let cube: Cube = Cube {
-        color: split[2],
-        num: split[1].parse::<u32>().unwrap(),
-    };
-    cube
+    let start = line.len() - line.trim_start().len();
+    let digits = line[start..]
+        .find(|c: char| !c.is_ascii_digit())
+        .map_or(line.len(), |i| start + i);
+    let num = line[start..digits]
+        .parse::<u32>()
+        .map_err(|_| error(start, "a number of cubes"))?;
+
+    if digits == line.len() || !line[digits..].starts_with(char::is_whitespace) {
+        return Err(error(digits, "a space before the colour"));
+    }
+
+    let colour_start = line.len() - line[digits..].trim_start().len();
+    let colour_end = line[colour_start..]
+        .find(|c: char| !c.is_alphabetic())
+        .map_or(line.len(), |i| colour_start + i);
+    let colour = &line[colour_start..colour_end];
+    if colour.is_empty() {
+        return Err(error(colour_start, "a colour"));
+    }
+    if !line[colour_end..].trim().is_empty() {
+        let rest = line.len() - line[colour_end..].trim_start().len();
+        return Err(error(rest, "',' or ';'"));
+    }
+
+    Ok(Cube {
+        colour: Colour::from(colour),
+        num,
+    })
 }
 
-fn get_game_number(line: &str) -> u32 {
-    line.split(':').collect::<Vec<&str>>()[0]
-        .split(' ')
-        .collect::<Vec<&str>>()[1]
+fn get_game_number(line: &str) -> Result<u32, ParseError> {
+    let trimmed = line.trim_start();
+    let start = line.len() - trimmed.len();
+
+    if !trimmed.starts_with("Game") {
+        return Err(get_error(line, start, "'Game'"));
+    }
+
+    let number = start + "Game".len();
+    line[number..]
+        .trim()
         .parse::<u32>()
-        .unwrap()
+        .map_err(|_| get_error(line, number, "a game number"))
 }
 
-fn get_min_set<'a>(g: &'a Game<'a>) -> Set<'a> {
-    let mut min_set: Set = Set { cubes: vec![] };
-
-    min_set.cubes.push(Cube {
-        color: "red",
-        num: 0,
-    });
-    min_set.cubes.push(Cube {
-        color: "green",
-        num: 0,
-    });
-    min_set.cubes.push(Cube {
-        color: "blue",
-        num: 0,
-    });
+// The fewest cubes of every colour of the bag and every other colour seen in the game.
+// The fewest cubes of every colour the game shows, colours it never shows are not in the set.
+fn get_min_set(g: &Game) -> Set {
+    let mut min: HashMap<Colour, u32> = HashMap::new();
 
     g.game.iter().for_each(|set: &Set| {
         set.cubes.iter().for_each(|cube: &Cube| {
-            min_set.cubes.iter_mut().for_each(|min_cube: &mut Cube| {
-                if cube.color == min_cube.color && cube.num > min_cube.num {
-                    min_cube.num = cube.num;
-                }
-            });
+            let num = min.entry(cube.colour.clone()).or_insert(0);
+            if cube.num > *num {
+                *num = cube.num;
+            }
         });
     });
 
+    let mut min_set: Set = Set {
+        cubes: min
+            .into_iter()
+            .map(|(colour, num)| Cube { colour, num })
+            .collect(),
+    };
+    min_set.cubes.sort_by(|a, b| a.colour.cmp(&b.colour));
     min_set
 }
 
+#[derive(Debug, PartialEq)]
+struct ColourStats {
+    colour: Colour,
+    max: u32,
+    mean: f64,
+    draws: usize, // number of sets the colour was drawn in
+}
+
+fn get_colour_stats(games: &[Game]) -> Vec<ColourStats> {
+    let mut draws: HashMap<Colour, Vec<u32>> = HashMap::new();
+
+    for set in games.iter().flat_map(|game| game.game.iter()) {
+        for cube in set.cubes.iter() {
+            draws.entry(cube.colour.clone()).or_default().push(cube.num);
+        }
+    }
+
+    let mut stats: Vec<ColourStats> = draws
+        .into_iter()
+        .map(|(colour, nums)| ColourStats {
+            colour,
+            max: nums.iter().copied().max().unwrap_or(0),
+            mean: nums.iter().sum::<u32>() as f64 / nums.len() as f64,
+            draws: nums.len(),
+        })
+        .collect();
+    stats.sort_by(|a, b| a.colour.cmp(&b.colour));
+    stats
+}
+
+fn get_possible_games(games: &[Game], bag: &Bag) -> Vec<u32> {
+    games
+        .iter()
+        .filter(|game| is_valid(game, bag))
+        .map(|game| game.game_num)
+        .collect()
+}
+
+// The smallest bag that makes every game possible.
+fn get_minimal_bag(games: &[Game]) -> Bag {
+    let mut bag = Bag::new();
+
+    for cube in games
+        .iter()
+        .flat_map(|game| game.game.iter())
+        .flat_map(|set| set.cubes.iter())
+    {
+        let num = bag.entry(cube.colour.clone()).or_insert(0);
+        *num = (*num).max(cube.num);
+    }
+
+    bag
+}
+
+fn format_bag(bag: &Bag) -> String {
+    let mut limits: Vec<(&Colour, &u32)> = bag.iter().collect();
+    limits.sort();
+    limits
+        .iter()
+        .map(|(colour, num)| format!("{}={}", colour, num))
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+fn get_report(games: &[Game], bags: &[Bag]) -> String {
+    let mut report: Vec<String> = vec![format!(
+        "{:<10} {:>5} {:>7} {:>6}",
+        "colour", "max", "mean", "draws"
+    )];
+
+    for stats in get_colour_stats(games) {
+        report.push(format!(
+            "{:<10} {:>5} {:>7.2} {:>6}",
+            stats.colour.to_string(),
+            stats.max,
+            stats.mean,
+            stats.draws
+        ));
+    }
+
+    let minimal = get_minimal_bag(games);
+    let mut candidates: Vec<(String, &Bag)> =
+        bags.iter().map(|bag| (format_bag(bag), bag)).collect();
+    candidates.push((format!("{} (minimal)", format_bag(&minimal)), &minimal));
+    let width = candidates
+        .iter()
+        .map(|(name, _)| name.len())
+        .max()
+        .unwrap_or(0)
+        .max(3);
+
+    report.push(String::new());
+    report.push(format!(
+        "{:<width$} {:>8} {:>8}  games",
+        "bag",
+        "possible",
+        "id sum",
+        width = width
+    ));
+    for (name, bag) in candidates {
+        let possible = get_possible_games(games, bag);
+        report.push(format!(
+            "{:<width$} {:>8} {:>8}  {}",
+            name,
+            format!("{}/{}", possible.len(), games.len()),
+            possible.iter().sum::<u32>(),
+            possible
+                .iter()
+                .map(|num| num.to_string())
+                .collect::<Vec<String>>()
+                .join(","),
+            width = width
+        ));
+    }
+
+    report.join("\n")
+}
+
 fn power(set: &Set) -> u32 {
     let mut power: u32 = 1;
     set.cubes.iter().for_each(|cube: &Cube| {
@@ -163,95 +435,241 @@ fn power(set: &Set) -> u32 {
 
 #[cfg(test)]
 mod tests {
-    #[test]
-    fn test_part1() {
-        let input: Vec<String> = vec![
+    use super::Colour;
+
+    fn get_test_input() -> Vec<String> {
+        vec![
             "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green".to_string(),
             "Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue".to_string(),
             "Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red".to_string(),
             "Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red".to_string(),
             "Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green".to_string(),
-        ];
-        assert_eq!(super::part1(input), 8);
+        ]
     }
 
+    #[test]
+    fn test_part1() {
+        assert_eq!(
+            super::part1(&get_test_input(), &super::get_default_bag()),
+            Ok(8)
+        );
+    }
+
+    #[test]
     fn test_part2() {
-        let input: Vec<String> = vec![
-            "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green".to_string(),
-            "Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue".to_string(),
-            "Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red".to_string(),
-            "Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red".to_string(),
-            "Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green".to_string(),
-        ];
-        assert_eq!(super::part2(input), 2286);
+        assert_eq!(super::part2(&get_test_input()), Ok(2286));
     }
 
     #[test]
     fn test_cube() {
         let line: &str = " 3 blue";
-        let cube: super::Cube = super::get_cube(line);
+        let cube: super::Cube = super::get_cube(line, 0, line.len()).unwrap();
         assert_eq!(cube.num, 3);
+        assert_eq!(cube.colour, Colour::from("blue"));
+
+        let cube: super::Cube = super::get_cube("  12\tmagenta ", 0, 13).unwrap();
+        assert_eq!(cube.num, 12);
+        assert_eq!(cube.colour, Colour::from("magenta"));
     }
 
     #[test]
     fn test_game_number() {
         let line: &str = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green";
-        let game_number = super::get_game_number(line);
+        let game_number = super::get_game(line).unwrap().game_num;
         assert_eq!(game_number, 1);
+        assert_eq!(super::get_game_number("Game   42 "), Ok(42));
     }
 
     #[test]
     fn test_set() {
         let line: &str = " 3 blue, 4 red";
-        let set: super::Set = super::get_set(line);
+        let set: super::Set = super::get_set(line, 0, line.len()).unwrap();
         assert_eq!(set.cubes.len(), 2);
         assert_eq!(set.cubes[0].num, 3);
-        assert_eq!(set.cubes[0].color, "blue");
+        assert_eq!(set.cubes[0].colour, Colour::from("blue"));
         assert_eq!(set.cubes[1].num, 4);
-        assert_eq!(set.cubes[1].color, "red");
+        assert_eq!(set.cubes[1].colour, Colour::from("red"));
     }
 
     #[test]
     fn test_game() {
         let line: &str = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green";
-        let game = super::get_game(line);
+        let game = super::get_game(line).unwrap();
         assert_eq!(game.game_num, 1);
         assert_eq!(game.game.len(), 3);
         assert_eq!(game.game[0].cubes.len(), 2);
         assert_eq!(game.game[0].cubes[0].num, 3);
-        assert_eq!(game.game[0].cubes[0].color, "blue");
+        assert_eq!(game.game[0].cubes[0].colour, Colour::from("blue"));
+
+        let spaced: super::Game = "Game 1 :3 blue,4 red;  1 red , 2 green,6 blue ;2 green"
+            .parse()
+            .unwrap();
+        assert_eq!(spaced, game);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = super::get_game("Game 1: 3 blue, 4red").unwrap_err();
+        assert_eq!(err.column, 18);
+        assert_eq!(err.expected, "a space before the colour");
+
+        let err = super::get_game("Game 1: 3 blue; x red").unwrap_err();
+        assert_eq!(err.column, 17);
+        assert_eq!(err.expected, "a number of cubes");
+        assert_eq!(err.found, "x");
+
+        let err = super::get_game("Game 1: 3 blue, 4 ").unwrap_err();
+        assert_eq!(err.column, 19);
+        assert_eq!(err.expected, "a colour");
+
+        let err = super::get_game("Game 1: 3 blue, 4 r3d").unwrap_err();
+        assert_eq!(err.column, 20);
+        assert_eq!(err.expected, "',' or ';'");
+
+        // columns count characters, also after colours that are not ASCII
+        let err = super::get_game("Game 1: 3 grün; 2 blü, 4rot").unwrap_err();
+        assert_eq!(err.column, 25);
+        assert_eq!(err.found, "rot");
+        assert_eq!(
+            err.to_string().lines().last(),
+            Some("  |                         ^^^")
+        );
+
+        let err = super::get_game("Gmae 1: 3 blue").unwrap_err();
+        assert_eq!(err.column, 1);
+        assert_eq!(err.expected, "'Game'");
+
+        let err = super::get_game("Game 1 3 blue").unwrap_err();
+        assert_eq!(err.expected, "':'");
+
+        let mut input = get_test_input();
+        input[3] = "Game 4: 1 green, 3 red, 6 blue; 3 green 6 red".to_string();
+        let err = super::part1(&input, &super::get_default_bag()).unwrap_err();
+        assert_eq!(err.line, 4);
+        assert_eq!(err.column, 41);
+        assert_eq!(
+            err.in_file("input.txt").to_string(),
+            "error: expected ',' or ';', found '6'\n --> input.txt:4:41\n  |\n4 | Game 4: 1 green, 3 red, 6 blue; 3 green 6 red\n  |                                         ^"
+        );
     }
 
     #[test]
     fn test_is_valid() {
+        let bag = super::get_default_bag();
+
         let line: &str = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green";
-        let game = super::get_game(line);
-        assert_eq!(super::is_valid(&game), true);
+        let game = super::get_game(line).unwrap();
+        assert!(super::is_valid(&game, &bag));
 
         let line: &str = "Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red";
-        let game = super::get_game(line);
-        assert_eq!(super::is_valid(&game), false);
+        let game = super::get_game(line).unwrap();
+        assert!(!super::is_valid(&game, &bag));
+
+        let game = super::get_game("Game 6: 1 purple, 2 red").unwrap();
+        assert!(!super::is_valid(&game, &bag));
+        let mut bag = bag;
+        bag.insert(Colour::from("purple"), 1);
+        assert!(super::is_valid(&game, &bag));
+    }
+
+    #[test]
+    fn test_get_bags() {
+        assert_eq!(super::get_bags(&[]), Ok(vec![super::get_default_bag()]));
+
+        let args = vec!["--bag".to_string(), "red=1, purple=3".to_string()];
+        let bag = &super::get_bags(&args).unwrap()[0];
+        assert_eq!(bag.len(), 2);
+        assert_eq!(bag[&Colour::from("red")], 1);
+        assert_eq!(bag[&Colour::from("purple")], 3);
+
+        let args = vec!["--bag".to_string(), "red=one".to_string()];
+        assert!(super::get_bags(&args).is_err());
+
+        let path = std::env::temp_dir().join(format!("day2-test-bag-{}.txt", std::process::id()));
+        std::fs::write(&path, "red 12\ngreen: 13\n\nblue = 14\n").unwrap();
+        let args = vec![
+            "--bag".to_string(),
+            "red=1".to_string(),
+            "--bag-file".to_string(),
+            path.to_string_lossy().to_string(),
+        ];
+        let bags = super::get_bags(&args).unwrap();
+        assert_eq!(bags.len(), 2);
+        assert_eq!(bags[1], super::get_default_bag());
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_get_colour_stats() {
+        let games = super::get_games(&get_test_input()).unwrap();
+        let stats = super::get_colour_stats(&games);
+
+        assert_eq!(stats.len(), 3);
+        assert_eq!(stats[0].colour, Colour::from("blue"));
+        assert_eq!(stats[0].max, 15);
+        assert_eq!(stats[0].draws, 11);
+        assert!((stats[0].mean - 50.0 / 11.0).abs() < 1e-9);
+        assert_eq!(stats[2].colour, Colour::from("red"));
+        assert_eq!(stats[2].max, 20);
+    }
+
+    #[test]
+    fn test_get_possible_games() {
+        let games = super::get_games(&get_test_input()).unwrap();
+
+        assert_eq!(
+            super::get_possible_games(&games, &super::get_default_bag()),
+            vec![1, 2, 5]
+        );
+
+        let minimal = super::get_minimal_bag(&games);
+        assert_eq!(super::format_bag(&minimal), "blue=15,green=13,red=20");
+        assert_eq!(
+            super::get_possible_games(&games, &minimal),
+            vec![1, 2, 3, 4, 5]
+        );
+    }
+
+    #[test]
+    fn test_get_report() {
+        let games = super::get_games(&get_test_input()).unwrap();
+        let report = super::get_report(&games, &[super::get_default_bag()]);
+        let lines: Vec<&str> = report.lines().collect();
+
+        assert_eq!(lines[0], "colour       max    mean  draws");
+        assert_eq!(lines[1], "blue          15    4.55     11");
+        assert_eq!(lines.len(), 8);
+        assert!(lines[6].starts_with("blue=14,green=13,red=12"));
+        assert!(lines[6].ends_with("3/5        8  1,2,5"));
+        assert!(lines[7].contains("(minimal)"));
+        assert!(lines[7].ends_with("5/5       15  1,2,3,4,5"));
     }
 
     #[test]
     fn test_min_set() {
         let line: &str = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green";
-        let game: super::Game = super::get_game(line);
+        let game: super::Game = super::get_game(line).unwrap();
         let min_set: super::Set = super::get_min_set(&game);
         assert_eq!(min_set.cubes.len(), 3);
-        assert_eq!(min_set.cubes[0].num, 4);
-        assert_eq!(min_set.cubes[0].color, "red");
+        assert_eq!(min_set.cubes[0].num, 6);
+        assert_eq!(min_set.cubes[0].colour, Colour::from("blue"));
         assert_eq!(min_set.cubes[1].num, 2);
-        assert_eq!(min_set.cubes[1].color, "green");
-        assert_eq!(min_set.cubes[2].num, 6);
-        assert_eq!(min_set.cubes[2].color, "blue");
+        assert_eq!(min_set.cubes[1].colour, Colour::from("green"));
+        assert_eq!(min_set.cubes[2].num, 4);
+        assert_eq!(min_set.cubes[2].colour, Colour::from("red"));
     }
 
     #[test]
     fn test_power() {
         let line: &str = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green";
-        let game: super::Game = super::get_game(line);
+        let game: super::Game = super::get_game(line).unwrap();
         let min_set: super::Set = super::get_min_set(&game);
         assert_eq!(super::power(&min_set), 48);
+
+        // only the colours of the game count, whatever the bag holds
+        let game: super::Game = super::get_game("Game 2: 2 red").unwrap();
+        assert_eq!(super::power(&super::get_min_set(&game)), 2);
+        let game: super::Game = super::get_game("Game 3: 2 red; 3 purple").unwrap();
+        assert_eq!(super::power(&super::get_min_set(&game)), 6);
     }
 }
@@ -1,208 +1,284 @@
-use std::fs::read_to_string;
+use std::collections::HashMap;
 
-fn main() {
-    let input = read_lines("./input.txt");
+use common::input::InputFiles;
+use common::parse::ParseError;
 
-    println!("Part 1: {}", part1(input.clone()));
-    println!("Part 2: {}", part2(input.clone()));
-}
+const INPUT: &str = "input.txt";
 
-fn read_lines(filename: &str) -> Vec<String> {
-    read_to_string(filename)
-        .unwrap() // panic on possible file-reading errors
-        .lines() // split the string into an iterator of string slices
-        .map(String::from) // make each slice into a string
-        .collect() // gather them together into a vector
-}
+fn main() {
+    let files = InputFiles::new(env!("CARGO_MANIFEST_DIR"), INPUT, INPUT);
 
-fn part1(input: Vec<String>) -> u32 {
-    let mut sum: u32 = 0;
+    if common::bench::requested() {
+        bench(&files);
+        return;
+    }
 
-    let parts: Vec<Part> = get_parts(input);
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let paths = common::input::take_paths(&mut args).unwrap_or_else(|err| {
+        eprintln!("error: {}", err);
+        std::process::exit(1);
+    });
 
-    parts.iter().for_each(|part: &Part| {
-        if part.is_part {
-            sum += part.num;
-        }
+    let solved = files.run(&paths, |input, _| {
+        run(&input.lines, &args).map_err(|err| err.in_file(&input.name))
     });
+    if !solved {
+        std::process::exit(1);
+    }
+}
+
+fn run(input: &[String], args: &[String]) -> Result<(), ParseError> {
+    println!("Part 1: {}", part1(input.to_vec())?);
+
+    if args.is_empty() {
+        println!("Part 2: {}", part2(input.to_vec())?);
+    } else {
+        let gear = get_gear_rule(args);
+        println!(
+            "Part 2: {}",
+            get_gear_ratio_sum(&get_schematic(input)?, &gear)
+        );
+    }
 
-    sum
+    Ok(())
 }
 
-fn part2(input: Vec<String>) -> u32 {
-    let mut sum: u32 = 0;
+fn bench(files: &InputFiles) {
+    let (input, _) = files.read_lines();
+
+    print!(
+        "{}",
+        common::bench::Bench::new()
+            .run("parse", || get_schematic(&input))
+            .run("part1", || part1(input.clone()))
+            .run("part2", || part2(input.clone()))
+            .report()
+    );
+}
 
-    let gears = get_gears(input);
+fn part1(input: Vec<String>) -> Result<u32, ParseError> {
+    let schematic = get_schematic(&input)?;
 
-    gears.iter().for_each(|gear| {
-        if gear.is_gear {
-            sum += gear.num;
-        }
-    });
+    Ok(get_part_numbers(&schematic).iter().sum())
+}
+
+fn part2(input: Vec<String>) -> Result<u64, ParseError> {
+    let schematic = get_schematic(&input)?;
 
-    sum
+    Ok(get_gear_ratio_sum(&schematic, &GearRule::default()))
 }
 
-#[derive(Debug)]
-struct Part {
-    num: u32,
-    is_part: bool,
+#[derive(Debug, PartialEq)]
+struct Number {
+    value: u32,
+    row: usize,
+    start: usize,
+    end: usize, // exclusive
 }
 
+#[derive(Debug, PartialEq)]
+struct Symbol {
+    c: char,
+    row: usize,
+    col: usize,
+}
+
+// Bipartite graph between numbers and the symbols touching them (diagonals included).
+// number_symbols[n] holds indices into symbols, symbol_numbers[s] indices into numbers.
 #[derive(Debug)]
-struct Gear {
-    num: u32,
-    is_gear: bool,
-    i: usize,
-    j: usize,
+struct Schematic {
+    numbers: Vec<Number>,
+    symbols: Vec<Symbol>,
+    number_symbols: Vec<Vec<usize>>,
+    symbol_numbers: Vec<Vec<usize>>,
 }
 
-fn is_part_char(c: char) -> bool {
-    !c.is_ascii_alphanumeric() && c != '.' && c != ' '
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Reduction {
+    Product,
+    Sum,
+    Max,
 }
 
-fn is_gear_char(c: char) -> bool {
-    c == '*'
+// Which symbols count as gears, how many numbers they have to touch
+// and how those numbers are combined into the gear ratio.
+#[derive(Debug, PartialEq)]
+struct GearRule {
+    symbols: Vec<char>,
+    neighbours: usize,
+    reduction: Reduction,
 }
 
-fn add_gear(gear: Gear, gears: &mut Vec<Gear>) {
-    for g in gears.iter_mut() {
-        if g.i == gear.i && g.j == gear.j {
-            g.is_gear = true;
-            g.num *= gear.num;
-            return;
+impl Default for GearRule {
+    fn default() -> Self {
+        GearRule {
+            symbols: vec!['*'],
+            neighbours: 2,
+            reduction: Reduction::Product,
         }
     }
+}
 
-    gears.push(gear);
+fn is_part_char(c: char) -> bool {
+    !c.is_ascii_alphanumeric() && c != '.' && c != ' '
 }
 
-fn get_gears(input: Vec<String>) -> Vec<Gear> {
-    let mut gears: Vec<Gear> = Vec::new();
-    let mut num: u32 = 0;
-    let mut is_gear: bool = false;
-    let mut gear_i: usize = 0;
-    let mut gear_j: usize = 0;
-
-    input.iter().enumerate().for_each(|(i, line)| {
-        num = 0;
-
-        line.chars().enumerate().for_each(|(j, c)| {
-            if c.is_ascii_digit() {
-                num *= 10;
-                num += c.to_digit(10).unwrap();
-
-                // todo figure out for loops
-                for x in 0..=2 {
-                    for y in 0..=2 {
-                        if i + x < input.len() + 1
-                            && j + y < line.len() + 1
-                            && i + x > 0
-                            && j + y > 0
-                            && is_gear_char(input[i + x - 1].chars().nth(j + y - 1).unwrap())
-                        {
-                            gear_i = i + x - 1;
-                            gear_j = j + y - 1;
-                            is_gear |= true;
-                        }
-                    }
+// --symbols "*#" --neighbours 3 --reduce sum|product|max
+fn get_gear_rule(args: &[String]) -> GearRule {
+    let mut gear = GearRule::default();
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        let value = args
+            .next()
+            .unwrap_or_else(|| panic!("missing value for {}", arg));
+
+        match arg.as_str() {
+            "--symbols" => gear.symbols = value.chars().collect(),
+            "--neighbours" => gear.neighbours = value.parse().expect("invalid neighbour count"),
+            "--reduce" => {
+                gear.reduction = match value.as_str() {
+                    "product" => Reduction::Product,
+                    "sum" => Reduction::Sum,
+                    "max" => Reduction::Max,
+                    _ => panic!("unknown reduction {}", value),
                 }
-            } else if num != 0 && is_gear {
-                add_gear(
-                    Gear {
-                        num,
-                        is_gear: false,
-                        i: gear_i,
-                        j: gear_j,
-                    },
-                    &mut gears,
-                );
-                is_gear = false;
-                num = 0;
-            } else {
-                num = 0;
             }
-        });
-
-        if num != 0 && is_gear {
-            add_gear(
-                Gear {
-                    num,
-                    is_gear: false,
-                    i: gear_i,
-                    j: gear_j,
-                },
-                &mut gears,
-            );
-            is_gear = false;
-            num = 0;
+            _ => panic!("unknown argument {}", arg),
         }
-    });
-    gears
+    }
+
+    gear
 }
 
-fn get_parts(input: Vec<String>) -> Vec<Part> {
-    let mut parts: Vec<Part> = Vec::new();
-    let mut num: u32 = 0;
-    let mut is_part: bool = false;
+// Digits, '.', spaces and symbols, letters are not part of a schematic.
+fn get_schematic(input: &[String]) -> Result<Schematic, ParseError> {
+    let mut schematic = Schematic {
+        numbers: Vec::new(),
+        symbols: Vec::new(),
+        number_symbols: Vec::new(),
+        symbol_numbers: Vec::new(),
+    };
+    let mut symbol_at: HashMap<(usize, usize), usize> = HashMap::new();
+
+    for (row, line) in input.iter().enumerate() {
+        let mut number: Option<Number> = None;
+        let mut number_byte = 0;
+
+        for (col, (byte, c)) in line.char_indices().enumerate() {
+            if let Some(digit) = c.to_digit(10) {
+                if number.is_none() {
+                    number_byte = byte;
+                }
+                let n = number.get_or_insert(Number {
+                    value: 0,
+                    row,
+                    start: col,
+                    end: col,
+                });
+                n.value = n
+                    .value
+                    .checked_mul(10)
+                    .and_then(|value| value.checked_add(digit))
+                    .ok_or_else(|| {
+                        common::parse::get_span_error(
+                            line,
+                            number_byte,
+                            byte + 1,
+                            "a number below 2^32",
+                        )
+                        .at_line(row + 1)
+                    })?;
+                n.end = col + 1;
+                continue;
+            }
 
-    input.iter().enumerate().for_each(|(i, line)| {
-        num = 0;
-        is_part = false;
+            if let Some(n) = number.take() {
+                schematic.numbers.push(n);
+            }
 
-        line.chars().enumerate().for_each(|(j, c)| {
-            if c.is_ascii_digit() {
-                num *= 10;
-                num += c.to_digit(10).unwrap();
+            if is_part_char(c) {
+                symbol_at.insert((row, col), schematic.symbols.len());
+                schematic.symbols.push(Symbol { c, row, col });
+            } else if c.is_alphabetic() {
+                return Err(common::parse::get_span_error(
+                    line,
+                    byte,
+                    byte + c.len_utf8(),
+                    "a digit, '.' or a symbol",
+                )
+                .at_line(row + 1));
+            }
+        }
 
-                // maybe refactor this later
-                if j < line.len() - 1 {
-                    is_part |= is_part_char(line.chars().nth(j + 1).unwrap());
-                }
-                if j > 0 {
-                    is_part |= is_part_char(line.chars().nth(j - 1).unwrap());
-                }
-                if i < input.len() - 1 {
-                    is_part |= is_part_char(input[i + 1].chars().nth(j).unwrap());
-                }
-                if i > 0 {
-                    is_part |= is_part_char(input[i - 1].chars().nth(j).unwrap());
-                }
-                if i < input.len() - 1 && j < line.len() - 1 {
-                    is_part |= is_part_char(input[i + 1].chars().nth(j + 1).unwrap());
-                }
-                if i > 0 && j > 0 {
-                    is_part |= is_part_char(input[i - 1].chars().nth(j - 1).unwrap());
-                }
-                if i < input.len() - 1 && j > 0 {
-                    is_part |= is_part_char(input[i + 1].chars().nth(j - 1).unwrap());
-                }
-                if i > 0 && j < line.len() - 1 {
-                    is_part |= is_part_char(input[i - 1].chars().nth(j + 1).unwrap());
+        if let Some(n) = number.take() {
+            schematic.numbers.push(n);
+        }
+    }
+
+    schematic.symbol_numbers = vec![Vec::new(); schematic.symbols.len()];
+
+    for (i, number) in schematic.numbers.iter().enumerate() {
+        let mut neighbours: Vec<usize> = Vec::new();
+
+        for row in number.row.saturating_sub(1)..=number.row + 1 {
+            for col in number.start.saturating_sub(1)..=number.end {
+                if let Some(&s) = symbol_at.get(&(row, col)) {
+                    neighbours.push(s);
+                    schematic.symbol_numbers[s].push(i);
                 }
-            } else if num != 0 {
-                parts.push(Part { num, is_part });
-                is_part = false;
-                num = 0;
             }
-        });
-
-        if num != 0 {
-            parts.push(Part { num, is_part });
-            is_part = false;
-            num = 0;
         }
-    });
-    parts
+
+        schematic.number_symbols.push(neighbours);
+    }
+
+    Ok(schematic)
+}
+
+fn get_part_numbers(schematic: &Schematic) -> Vec<u32> {
+    schematic
+        .numbers
+        .iter()
+        .zip(schematic.number_symbols.iter())
+        .filter(|(_, symbols)| !symbols.is_empty())
+        .map(|(number, _)| number.value)
+        .collect()
+}
+
+// Indices of all symbols that touch exactly `count` numbers.
+fn get_symbols_with_neighbours(schematic: &Schematic, count: usize) -> Vec<usize> {
+    schematic
+        .symbol_numbers
+        .iter()
+        .enumerate()
+        .filter(|(_, numbers)| numbers.len() == count)
+        .map(|(s, _)| s)
+        .collect()
+}
+
+fn get_gear_ratio_sum(schematic: &Schematic, gear: &GearRule) -> u64 {
+    get_symbols_with_neighbours(schematic, gear.neighbours)
+        .iter()
+        .filter(|&&s| gear.symbols.contains(&schematic.symbols[s].c))
+        .map(|&s| {
+            let values = schematic.symbol_numbers[s]
+                .iter()
+                .map(|&n| schematic.numbers[n].value as u64);
+
+            match gear.reduction {
+                Reduction::Product => values.product::<u64>(),
+                Reduction::Sum => values.sum::<u64>(),
+                Reduction::Max => values.max().unwrap_or(0),
+            }
+        })
+        .sum()
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::get_parts;
-
-    #[test]
-    fn test_part1() {
-        let input: Vec<String> = vec![
+    fn get_test_input() -> Vec<String> {
+        vec![
             "467..114..".to_string(),
             "...*......".to_string(),
             "..35..633.".to_string(),
@@ -213,11 +289,16 @@ mod tests {
             "......755.".to_string(),
             "...$.*....".to_string(),
             ".664.598..".to_string(),
-        ];
-        assert_eq!(super::part1(input), 4361);
+        ]
     }
 
     #[test]
+    fn test_part1() {
+        assert_eq!(super::part1(get_test_input()), Ok(4361));
+    }
+
+    #[test]
+    #[allow(clippy::bool_assert_comparison)]
     fn test_is_part_char() {
         assert_eq!(super::is_part_char('.'), false);
         assert_eq!(super::is_part_char('*'), true);
@@ -231,133 +312,166 @@ mod tests {
     }
 
     #[test]
-    fn test_get_parts() {
-        let input: Vec<String> = vec![
-            "467..114..".to_string(),
-            "...*......".to_string(),
-            "..35..633.".to_string(),
-            "......#...".to_string(),
-            "617*......".to_string(),
-            ".....+.58.".to_string(),
-            "..592.....".to_string(),
-            "......755.".to_string(),
-            "...$.*....".to_string(),
-            ".664.598..".to_string(),
-        ];
-        let parts: Vec<super::Part> = get_parts(input);
-
-        dbg!(&parts);
-        assert_eq!(parts[0].num, 467);
-        assert_eq!(parts[0].is_part, true);
-        assert_eq!(parts[1].num, 114);
-        assert_eq!(parts[1].is_part, false);
-        assert_eq!(parts[2].num, 35);
-        assert_eq!(parts[2].is_part, true);
-        assert_eq!(parts[3].num, 633);
-        assert_eq!(parts[3].is_part, true);
-        assert_eq!(parts[4].num, 617);
-        assert_eq!(parts[4].is_part, true);
-        assert_eq!(parts[5].num, 58);
-        assert_eq!(parts[5].is_part, false);
-        assert_eq!(parts[6].num, 592);
-        assert_eq!(parts[6].is_part, true);
-        assert_eq!(parts[7].num, 755);
-        assert_eq!(parts[7].is_part, true);
-        assert_eq!(parts[8].num, 664);
-        assert_eq!(parts[8].is_part, true);
-        assert_eq!(parts[9].num, 598);
-        assert_eq!(parts[9].is_part, true);
+    fn test_get_schematic_errors() {
+        let mut input = get_test_input();
+        input[5] = ".....+.5B.".to_string();
+        let err = super::get_schematic(&input).unwrap_err();
+        assert_eq!((err.line, err.column, err.width), (6, 9, 1));
+        assert_eq!(err.expected, "a digit, '.' or a symbol");
+
+        input[5] = "..99999999999*".to_string();
+        let err = super::get_schematic(&input).unwrap_err();
+        assert_eq!(
+            (err.line, err.column, err.found.as_str()),
+            (6, 3, "9999999999")
+        );
+    }
+
+    #[test]
+    fn test_get_schematic() {
+        let schematic = super::get_schematic(&get_test_input()).unwrap();
+
+        let parts: Vec<(u32, bool)> = schematic
+            .numbers
+            .iter()
+            .zip(schematic.number_symbols.iter())
+            .map(|(number, symbols)| (number.value, !symbols.is_empty()))
+            .collect();
+
+        assert_eq!(
+            parts,
+            vec![
+                (467, true),
+                (114, false),
+                (35, true),
+                (633, true),
+                (617, true),
+                (58, false),
+                (592, true),
+                (755, true),
+                (664, true),
+                (598, true),
+            ]
+        );
+        assert_eq!(
+            schematic.numbers[3],
+            super::Number {
+                value: 633,
+                row: 2,
+                start: 6,
+                end: 9
+            }
+        );
+        assert_eq!(schematic.symbols.len(), 6);
+        assert_eq!(
+            schematic.symbols[0],
+            super::Symbol {
+                c: '*',
+                row: 1,
+                col: 3
+            }
+        );
+        assert_eq!(schematic.symbol_numbers[0], vec![0, 2]);
+    }
+
+    #[test]
+    fn test_get_part_numbers() {
+        let schematic = super::get_schematic(&get_test_input()).unwrap();
+        let parts = super::get_part_numbers(&schematic);
+        assert_eq!(parts.len(), 8);
+        assert!(!parts.contains(&114));
+        assert!(!parts.contains(&58));
     }
 
     #[test]
     fn test_part2() {
-        let input: Vec<String> = vec![
-            "467..114..".to_string(),
-            "...*......".to_string(),
-            "..35..633.".to_string(),
-            "......#...".to_string(),
-            "617*......".to_string(),
-            ".....+.58.".to_string(),
-            "..592.....".to_string(),
-            "......755.".to_string(),
-            "...$.*....".to_string(),
-            ".664.598..".to_string(),
-        ];
-        assert_eq!(super::part2(input), 467835);
+        assert_eq!(super::part2(get_test_input()), Ok(467835));
+    }
+
+    #[test]
+    fn test_get_gear_rule() {
+        assert_eq!(super::get_gear_rule(&[]), super::GearRule::default());
+
+        let args: Vec<String> = ["--symbols", "*#", "--neighbours", "3", "--reduce", "max"]
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect();
+        assert_eq!(
+            super::get_gear_rule(&args),
+            super::GearRule {
+                symbols: vec!['*', '#'],
+                neighbours: 3,
+                reduction: super::Reduction::Max,
+            }
+        );
     }
 
     #[test]
-    fn test_is_gear_char() {
-        assert_eq!(super::is_gear_char('.'), false);
-        assert_eq!(super::is_gear_char('*'), true);
-        assert_eq!(super::is_gear_char('$'), false);
-        assert_eq!(super::is_gear_char('+'), false);
-        assert_eq!(super::is_gear_char('#'), false);
-        assert_eq!(super::is_gear_char('a'), false);
-        assert_eq!(super::is_gear_char('A'), false);
-        assert_eq!(super::is_gear_char('1'), false);
-        assert_eq!(super::is_gear_char(' '), false);
+    fn test_get_gear_ratio_sum() {
+        let schematic = super::get_schematic(&get_test_input()).unwrap();
+
+        let mut gear = super::GearRule::default();
+        assert_eq!(super::get_gear_ratio_sum(&schematic, &gear), 467835);
+
+        gear.reduction = super::Reduction::Sum;
+        assert_eq!(
+            super::get_gear_ratio_sum(&schematic, &gear),
+            467 + 35 + 755 + 598
+        );
+
+        gear.reduction = super::Reduction::Max;
+        assert_eq!(super::get_gear_ratio_sum(&schematic, &gear), 467 + 755);
+
+        gear.symbols = vec!['#', '+', '$'];
+        gear.neighbours = 1;
+        assert_eq!(
+            super::get_gear_ratio_sum(&schematic, &gear),
+            633 + 592 + 664
+        );
     }
 
     #[test]
-    fn test_add_gear() {
-        let mut gears: Vec<super::Gear> = Vec::new();
-        let gear: super::Gear = super::Gear {
-            num: 2,
-            is_gear: false,
-            i: 0,
-            j: 0,
+    fn test_star_touching_three_numbers() {
+        let input: Vec<String> = vec!["2.3".to_string(), ".*.".to_string(), ".4.".to_string()];
+        let schematic = super::get_schematic(&input).unwrap();
+
+        assert_eq!(super::part2(input), Ok(0));
+
+        let mut gear = super::GearRule {
+            symbols: vec!['*'],
+            neighbours: 3,
+            reduction: super::Reduction::Product,
         };
-        super::add_gear(gear, &mut gears);
+        assert_eq!(super::get_gear_ratio_sum(&schematic, &gear), 24);
 
-        dbg!(&gears);
+        gear.reduction = super::Reduction::Sum;
+        assert_eq!(super::get_gear_ratio_sum(&schematic, &gear), 9);
 
-        assert_eq!(gears.len(), 1);
-        assert_eq!(gears[0].num, 2);
-        assert_eq!(gears[0].is_gear, false);
-        assert_eq!(gears[0].i, 0);
-        assert_eq!(gears[0].j, 0);
+        gear.reduction = super::Reduction::Max;
+        assert_eq!(super::get_gear_ratio_sum(&schematic, &gear), 4);
+    }
 
-        let gear: super::Gear = super::Gear {
-            num: 3,
-            is_gear: false,
-            i: 0,
-            j: 0,
-        };
-        super::add_gear(gear, &mut gears);
-
-        dbg!(&gears);
-
-        assert_eq!(gears.len(), 1);
-        assert_eq!(gears[0].num, 6);
-        assert_eq!(gears[0].is_gear, true);
-        assert_eq!(gears[0].i, 0);
-        assert_eq!(gears[0].j, 0);
-
-        // let mut gear: super::Gear = super::Gear {
-        //     num: 1,
-        //     is_gear: false,
-        //     i: 0,
-        //     j: 1,
-        // };
-        // super::add_gear(&mut gear, &mut gears);
-        // assert_eq!(gears.len(), 2);
-        // assert_eq!(gears[1].num, 1);
-        // assert_eq!(gears[1].is_gear, false);
-        // assert_eq!(gears[1].i, 0);
-        // assert_eq!(gears[1].j, 1);
-
-        // let mut gear: super::Gear = super::Gear {
-        //     num: 1,
-        //     is_gear: false,
-        //     i: 0,
-        //     j: 1,
-        // };
-        // super::add_gear(&mut gear, &mut gears);
-        // assert_eq!(gears.len(), 2);
-        // assert_eq!(gears[1].num, 1);
-        // assert_eq!(gears[1].is_gear, true);
-        // assert_eq!(gears[1].i, 0);
-        // assert_eq!(gears[1].j, 1);
+    #[test]
+    fn test_get_symbols_with_neighbours() {
+        let schematic = super::get_schematic(&get_test_input()).unwrap();
+
+        let gears = super::get_symbols_with_neighbours(&schematic, 2);
+        assert_eq!(gears.len(), 2);
+        assert_eq!(schematic.symbols[gears[0]].c, '*');
+        assert_eq!(schematic.symbols[gears[1]].c, '*');
+
+        let single = super::get_symbols_with_neighbours(&schematic, 1);
+        assert_eq!(single.len(), 4);
+    }
+
+    #[test]
+    fn test_number_next_to_two_stars() {
+        let input: Vec<String> = vec!["2.3".to_string(), "*.*".to_string(), ".5.".to_string()];
+        let schematic = super::get_schematic(&input).unwrap();
+
+        assert_eq!(schematic.number_symbols[2], vec![0, 1]);
+        assert_eq!(schematic.symbol_numbers[0], vec![0, 2]);
+        assert_eq!(schematic.symbol_numbers[1], vec![1, 2]);
+        assert_eq!(super::part2(input), Ok(2 * 5 + 3 * 5));
     }
 }
@@ -1,162 +1,441 @@
-use num_traits::{pow, AsPrimitive};
-use std::fs::read_to_string;
+use std::collections::HashSet;
+use std::str::FromStr;
+
+use common::input::InputFiles;
+use common::parse::{get_error, get_span_error, ParseError};
+
+const INPUT: &str = "input.txt";
 
 fn main() {
-    let input = read_lines("./input.txt");
+    let files = InputFiles::new(env!("CARGO_MANIFEST_DIR"), INPUT, INPUT);
+
+    if common::bench::requested() {
+        bench(&files);
+        return;
+    }
 
-    println!("Part 1: {}", part1(&input));
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let paths = common::input::take_paths(&mut args).unwrap_or_else(|err| {
+        eprintln!("error: {}", err);
+        std::process::exit(1);
+    });
+    let trace = match args.iter().map(|s| s.as_str()).collect::<Vec<_>>()[..] {
+        [] => None,
+        ["--trace"] | ["--trace", "csv"] => Some("csv"),
+        ["--trace", "json"] => Some("json"),
+        _ => {
+            eprintln!("usage: day4 [--trace [csv|json]] [--input file...]");
+            std::process::exit(1);
+        }
+    };
 
-    println!("Part 2: {}", part2(&input));
-}
-fn read_lines(filename: &str) -> Vec<String> {
-    read_to_string(filename)
-        .unwrap() // panic on possible file-reading errors
-        .lines() // split the string into an iterator of string slices
-        .map(String::from) // make each slice into a string
-        .collect() // gather them together into a vector
+    let solved = files.run(&paths, |file, _| {
+        let input = &file.lines;
+        let result = match trace {
+            None => part1(input).and_then(|part1| {
+                println!("Part 1: {}", part1);
+
+                println!("Part 2: {}", part2(input)?);
+                Ok(())
+            }),
+            Some("json") => {
+                get_cards(input).map(|cards| println!("{}", trace_json(&get_trace(&cards))))
+            }
+            Some(_) => get_cards(input).map(|cards| print!("{}", trace_csv(&get_trace(&cards)))),
+        };
+        result.map_err(|err| err.in_file(&file.name))
+    });
+    if !solved {
+        std::process::exit(1);
+    }
 }
 
-fn part1(input: &[String]) -> u32 {
-    let mut sum = 0;
+fn bench(files: &InputFiles) {
+    let (input, _) = files.read_lines();
+
+    print!(
+        "{}",
+        common::bench::Bench::new()
+            .run("parse", || get_cards(&input))
+            .run("part1", || part1(&input))
+            .run("part2", || part2(&input))
+            .report()
+    );
+}
 
-    let input_clean = input
+fn part1(input: &[String]) -> Result<u32, ParseError> {
+    Ok(get_cards(input)?
         .iter()
-        .map(|line| line.replace("  ", " "))
-        .collect::<Vec<String>>();
-
-    for line in input_clean {
-        dbg!(&line);
-        let mut points: u32 = 0;
-        let card: Card = Card {
-            number: 0,
-            winning: to_vec_u32(
-                line.as_str().split(": ").collect::<Vec<&str>>()[1]
-                    .split(" | ")
-                    .collect::<Vec<&str>>()[0],
-            ),
-            having: to_vec_u32(
-                line.as_str().split(": ").collect::<Vec<&str>>()[1]
-                    .split(" | ")
-                    .collect::<Vec<&str>>()[1],
-            ),
-        };
+        .map(|card| match card.matches() {
+            0 => 0,
+            matches => 1 << (matches - 1),
+        })
+        .sum())
+}
 
-        card.winning.iter().for_each(|win| {
-            card.having.iter().for_each(|have| {
-                if win == have {
-                    points += 1;
-                }
-            })
-        });
+fn part2(input: &[String]) -> Result<u64, ParseError> {
+    Ok(get_copies(&get_cards(input)?).iter().sum())
+}
+
+#[derive(Debug, PartialEq)]
+struct Card {
+    id: usize,
+    winning: Vec<u32>,
+    having: Vec<u32>,
+}
+
+// Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+impl FromStr for Card {
+    type Err = ParseError;
 
-        if points != 0 {
-            sum += pow(2u32, (points - 1).try_into().unwrap());
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let colon = line
+            .find(':')
+            .ok_or_else(|| get_error(line, line.len(), "':'"))?;
+
+        let start = line.len() - line.trim_start().len();
+        if !line[start..].starts_with("Card") {
+            return Err(get_error(line, start, "'Card'"));
+        }
+        let number = line.len() - line[start + "Card".len()..].trim_start().len();
+        let end = number + line[number..colon].trim_end().len();
+        let id = line[number..end]
+            .parse::<usize>()
+            .map_err(|_| get_span_error(line, number, end, "a card number"))?;
+
+        let bar = line[colon..]
+            .find('|')
+            .map(|i| colon + i)
+            .ok_or_else(|| get_error(line, line.len(), "'|'"))?;
+
+        Ok(Card {
+            id,
+            winning: common::parse::get_numbers(line, colon + 1..bar)?,
+            having: common::parse::get_numbers(line, bar + 1..line.len())?,
+        })
+    }
+}
+
+impl Card {
+    // How many different numbers we have are winning ones, a number we have twice counts once.
+    fn matches(&self) -> usize {
+        match (get_mask(&self.winning), get_mask(&self.having)) {
+            (Some(winning), Some(having)) => (winning & having).count_ones() as usize,
+            _ => {
+                let winning: HashSet<&u32> = self.winning.iter().collect();
+                let having: HashSet<&u32> = self.having.iter().collect();
+                winning.intersection(&having).count()
+            }
         }
     }
-    sum
 }
 
-fn part2(input: &[String]) -> u32 {
-    let mut sum = 0;
+// All numbers as bits of a single mask, None if one of them does not fit.
+fn get_mask(numbers: &[u32]) -> Option<u128> {
+    numbers.iter().try_fold(0_u128, |mask, &number| {
+        1_u128.checked_shl(number).map(|bit| mask | bit)
+    })
+}
 
-    let input_clean = input
-        .iter()
-        .map(|line| line.replace("  ", " "))
-        .collect::<Vec<String>>();
+fn get_cards(input: &[String]) -> Result<Vec<Card>, ParseError> {
+    common::parse::parse_lines(input, str::parse::<Card>)
+}
+
+// How many instances of every card we end up with, in the order of the table.
+// A card with id n and m matches wins one copy each of the cards n + 1 ..= n + m,
+// as long as they are in the table. The copies are spread with a difference array.
+fn get_copies(cards: &[Card]) -> Vec<u64> {
+    let mut order: Vec<usize> = (0..cards.len()).collect();
+    order.sort_by_key(|&i| cards[i].id);
+    let ids: Vec<usize> = order.iter().map(|&i| cards[i].id).collect();
+
+    let mut extra = vec![0_i64; cards.len() + 1];
+    let mut running: i64 = 0;
+    let mut copies = vec![0_u64; cards.len()];
+
+    for (position, &i) in order.iter().enumerate() {
+        running += extra[position];
+        let count = 1 + running;
+        copies[i] = count as u64;
+
+        let card = &cards[i];
+        let from = ids.partition_point(|&id| id <= card.id);
+        let to = ids.partition_point(|&id| id <= card.id + card.matches());
+        if from < to {
+            extra[from] += count;
+            extra[to] -= count;
+        }
+    }
+
+    copies
+}
+
+// One row of the copy cascade, per card in id order.
+#[derive(Debug, PartialEq)]
+struct TraceRow {
+    id: usize,
+    matches: usize,
+    instances: u64,
+    // (card id, copies) of the earlier cards that won copies of this one
+    sources: Vec<(usize, u64)>,
+    // copies this card won of ids that are not in the table
+    lost: u64,
+    // instances of this and all earlier cards
+    total: u64,
+}
+
+fn get_trace(cards: &[Card]) -> Vec<TraceRow> {
+    let mut order: Vec<&Card> = cards.iter().collect();
+    order.sort_by_key(|card| card.id);
 
-    let mut cards = input_clean
+    let mut rows: Vec<TraceRow> = order
         .iter()
-        .map(|line| {
-            dbg!(&line);
-            Card {
-                number: 1,
-                winning: to_vec_u32(
-                    line.as_str().split(": ").collect::<Vec<&str>>()[1]
-                        .split(" | ")
-                        .collect::<Vec<&str>>()[0],
-                ),
-                having: to_vec_u32(
-                    line.as_str().split(": ").collect::<Vec<&str>>()[1]
-                        .split(" | ")
-                        .collect::<Vec<&str>>()[1],
-                ),
-            }
+        .map(|card| TraceRow {
+            id: card.id,
+            matches: card.matches(),
+            instances: 1,
+            sources: Vec::new(),
+            lost: 0,
+            total: 0,
         })
-        .collect::<Vec<Card>>();
+        .collect();
 
-    for i in 0..cards.len() {
-        let mut points: usize = 0;
+    let mut total = 0;
+    for position in 0..rows.len() {
+        let (done, rest) = rows.split_at_mut(position + 1);
+        let row = &mut done[position];
+        total += row.instances;
+        row.total = total;
 
-        cards[i].winning.iter().for_each(|win| {
-            cards[i].having.iter().for_each(|have| {
-                if win == have {
-                    points += 1;
-                }
-            })
-        });
-
-        sum += cards[i].number;
-
-        for _ in 1..=cards[i].number {
-            //🙃 
-            if points > 0 {
-                for j in 1..=points {
-                    if i + j < cards.len() {
-                        cards[i + j].number += 1;
-                    }
+        for id in row.id + 1..=row.id + row.matches {
+            match rest.iter_mut().find(|target| target.id == id) {
+                Some(target) => {
+                    target.instances += row.instances;
+                    target.sources.push((row.id, row.instances));
                 }
+                None => row.lost += row.instances,
             }
         }
     }
-    sum.as_()
+
+    rows
 }
 
-#[derive(Debug)]
-struct Card {
-    number: usize,
-    winning: Vec<u32>,
-    having: Vec<u32>,
+fn trace_csv(rows: &[TraceRow]) -> String {
+    let mut csv = String::from("card,matches,instances,won,from,lost,total\n");
+    for row in rows {
+        let from: Vec<String> = row
+            .sources
+            .iter()
+            .map(|(id, copies)| format!("{}:{}", id, copies))
+            .collect();
+        csv += &format!(
+            "{},{},{},{},{},{},{}\n",
+            row.id,
+            row.matches,
+            row.instances,
+            row.instances - 1,
+            from.join(";"),
+            row.lost,
+            row.total
+        );
+    }
+    csv
 }
 
-fn to_vec_u32(line: &str) -> Vec<u32> {
-    line.split(' ')
-        .collect::<Vec<&str>>()
+fn trace_json(rows: &[TraceRow]) -> String {
+    let rows: Vec<String> = rows
         .iter()
-        .map(|&s| s.parse::<u32>().expect("Failed to parse u32"))
-        .collect::<Vec<u32>>()
+        .map(|row| {
+            let from: Vec<String> = row
+                .sources
+                .iter()
+                .map(|(id, copies)| format!("{{\"card\": {}, \"copies\": {}}}", id, copies))
+                .collect();
+            format!(
+                "  {{\"card\": {}, \"matches\": {}, \"instances\": {}, \"won\": {}, \"from\": [{}], \"lost\": {}, \"total\": {}}}",
+                row.id,
+                row.matches,
+                row.instances,
+                row.instances - 1,
+                from.join(", "),
+                row.lost,
+                row.total
+            )
+        })
+        .collect();
+    format!("[\n{}\n]", rows.join(",\n"))
 }
 
 #[cfg(test)]
 mod tests {
-    #[test]
-    fn test_part1() {
-        let input: Vec<String> = vec![
+    fn get_test_input() -> Vec<String> {
+        vec![
             "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53".to_string(),
             "Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19".to_string(),
             "Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1".to_string(),
             "Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83".to_string(),
             "Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36".to_string(),
             "Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11".to_string(),
-        ];
-        assert_eq!(super::part1(&input), 13);
+        ]
+    }
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(super::part1(&get_test_input()), Ok(13));
     }
 
     #[test]
     fn test_part2() {
-        let input: Vec<String> = vec![
-            "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53".to_string(),
-            "Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19".to_string(),
-            "Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1".to_string(),
-            "Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83".to_string(),
-            "Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36".to_string(),
-            "Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11".to_string(),
-        ];
-        assert_eq!(super::part2(&input), 30);
+        assert_eq!(super::part2(&get_test_input()), Ok(30));
+    }
+
+    #[test]
+    fn test_card_errors() {
+        let error = |line: &str| {
+            let err = line.parse::<super::Card>().unwrap_err();
+            (err.column, err.width, err.expected)
+        };
+        assert_eq!(error("Card 1: 41 x | 3"), (12, 1, "u32".to_string()));
+        assert_eq!(error("Card 1: 41 | 3 -"), (16, 1, "u32".to_string()));
+        assert_eq!(
+            error("Card  x1 : 41 | 3"),
+            (7, 2, "a card number".to_string())
+        );
+        assert_eq!(error("Crad 1: 41 | 3"), (1, 4, "'Card'".to_string()));
+        assert_eq!(error("Card 1: 41 3"), (13, 1, "'|'".to_string()));
+
+        let mut input = get_test_input();
+        input[2] = "Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1 x".to_string();
+        assert_eq!(super::part2(&input).unwrap_err().line, 3);
+    }
+
+    #[test]
+    fn test_card_from_str() {
+        let card: super::Card = "Card  13:  1 21 | 69  1".parse().unwrap();
+        assert_eq!(
+            card,
+            super::Card {
+                id: 13,
+                winning: vec![1, 21],
+                having: vec![69, 1],
+            }
+        );
+        assert!("Card x: 1 | 2".parse::<super::Card>().is_err());
+        assert!("Card 1: 1 2".parse::<super::Card>().is_err());
+        assert!("1 | 2".parse::<super::Card>().is_err());
     }
 
     #[test]
-    fn test_to_vec_u32() {
-        let line: &str = "41 48 83 86 17";
-        assert_eq!(super::to_vec_u32(&line), [41, 48, 83, 86, 17]);
+    fn test_matches() {
+        let card: super::Card = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53"
+            .parse()
+            .unwrap();
+        assert_eq!(card.matches(), 4);
+
+        // too big for the mask
+        let card: super::Card = "Card 1: 1000 48 | 48 1000 3".parse().unwrap();
+        assert_eq!(card.matches(), 2);
+        assert_eq!(super::get_mask(&[1000]), None);
+        assert_eq!(super::get_mask(&[0, 127]), Some(1 | 1 << 127));
+
+        // duplicates count once, with the mask and without it
+        let card: super::Card = "Card 1: 48 3 48 | 48 48 3 3 5".parse().unwrap();
+        assert_eq!(card.matches(), 2);
+        let card: super::Card = "Card 1: 1000 3 1000 | 1000 1000 3 3 5".parse().unwrap();
+        assert_eq!(card.matches(), 2);
+    }
+
+    #[test]
+    fn test_get_copies() {
+        let cards: Vec<super::Card> = get_test_input()
+            .iter()
+            .map(|line| line.parse().unwrap())
+            .collect();
+        assert_eq!(super::get_copies(&cards), vec![1, 2, 4, 8, 14, 1]);
+
+        // wins past the end of the table are dropped, ids decide which cards get copies
+        let cards: Vec<super::Card> = [
+            "Card 5: 1 2 | 1 2",
+            "Card 2: 1 2 3 | 1 2 3",
+            "Card 4: 9 | 8",
+        ]
+        .iter()
+        .map(|line| line.parse().unwrap())
+        .collect();
+        assert_eq!(super::get_copies(&cards), vec![2, 1, 2]);
+    }
+
+    #[test]
+    fn test_get_trace() {
+        let cards: Vec<super::Card> = get_test_input()
+            .iter()
+            .map(|line| line.parse().unwrap())
+            .collect();
+        let trace = super::get_trace(&cards);
+
+        let instances: Vec<u64> = trace.iter().map(|row| row.instances).collect();
+        assert_eq!(instances, super::get_copies(&cards));
+        assert_eq!(trace.last().unwrap().total, 30);
+        assert_eq!(trace[3].sources, vec![(1, 1), (2, 2), (3, 4)]);
+        assert!(trace.iter().all(|row| row.lost == 0));
+
+        // card 5 wins copies of 6 and 7, which are not in the table
+        let cards: Vec<super::Card> = [
+            "Card 5: 1 2 | 1 2",
+            "Card 2: 1 2 3 | 1 2 3",
+            "Card 4: 9 | 8",
+        ]
+        .iter()
+        .map(|line| line.parse().unwrap())
+        .collect();
+        let trace = super::get_trace(&cards);
+        assert_eq!(
+            trace,
+            vec![
+                super::TraceRow {
+                    id: 2,
+                    matches: 3,
+                    instances: 1,
+                    sources: vec![],
+                    lost: 1,
+                    total: 1,
+                },
+                super::TraceRow {
+                    id: 4,
+                    matches: 0,
+                    instances: 2,
+                    sources: vec![(2, 1)],
+                    lost: 0,
+                    total: 3,
+                },
+                super::TraceRow {
+                    id: 5,
+                    matches: 2,
+                    instances: 2,
+                    sources: vec![(2, 1)],
+                    lost: 4,
+                    total: 5,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_trace_output() {
+        let cards: Vec<super::Card> = ["Card 1: 1 2 | 1 2", "Card 2: 3 | 4"]
+            .iter()
+            .map(|line| line.parse().unwrap())
+            .collect();
+        let trace = super::get_trace(&cards);
+
+        assert_eq!(
+            super::trace_csv(&trace),
+            "card,matches,instances,won,from,lost,total\n1,2,1,0,,1,1\n2,0,2,1,1:1,0,3\n"
+        );
+        assert_eq!(
+            super::trace_json(&trace),
+            "[\n  {\"card\": 1, \"matches\": 2, \"instances\": 1, \"won\": 0, \"from\": [], \"lost\": 1, \"total\": 1},\n  {\"card\": 2, \"matches\": 0, \"instances\": 2, \"won\": 1, \"from\": [{\"card\": 1, \"copies\": 1}], \"lost\": 0, \"total\": 3}\n]"
+        );
     }
 }
@@ -1,30 +1,62 @@
 use std::fmt::Debug;
 
-use common;
+use common::input::InputFiles;
+use common::parse::{ParseError, Pattern};
+
+const INPUT: &str = "input.txt";
 
 fn main() {
-    let input = common::read_lines("./input.txt");
+    let files = InputFiles::new(env!("CARGO_MANIFEST_DIR"), INPUT, INPUT);
+
+    if common::bench::requested() {
+        bench(&files);
+        return;
+    }
 
-    println!("Part 1: {}", part1(&input));
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let paths = common::input::take_paths(&mut args).unwrap_or_else(|err| {
+        eprintln!("error: {}", err);
+        std::process::exit(1);
+    });
 
-    let input = common::read_lines("./input.txt");
-    println!("Part 2: {}", part2(&input));
+    let solved = files.run(&paths, |input, _| -> Result<(), ParseError> {
+        let in_file = |err: ParseError| err.in_file(&input.name);
+        println!("Part 1: {}", part1(&input.lines).map_err(in_file)?);
+        println!("Part 2: {}", part2(&input.lines).map_err(in_file)?);
+        Ok(())
+    });
+    if !solved {
+        std::process::exit(1);
+    }
 }
 
-fn part1(input: &[String]) -> u64 {
-    let almanac = get_almanac(input);
+fn bench(files: &InputFiles) {
+    let (input, _) = files.read_lines();
+
+    // part 2 is a brute force over all seeds and takes hours, so it is left out
+    print!(
+        "{}",
+        common::bench::Bench::new()
+            .run("parse", || get_almanac(&input))
+            .run("part1", || part1(&input))
+            .report()
+    );
+}
+
+fn part1(input: &[String]) -> Result<u64, ParseError> {
+    let almanac = get_almanac(input)?;
     let mut locations: Vec<u64> = Vec::new();
     almanac.seeds.iter().for_each(|seed| {
         locations.push(traverse_almanac(&almanac, *seed));
     });
     let location = locations.iter().min().unwrap();
-    *location
+    Ok(*location)
 }
 
-fn part2(input: &[String]) -> u64 {
-    let almanac = get_almanac(input);
+fn part2(input: &[String]) -> Result<u64, ParseError> {
+    let almanac = get_almanac(input)?;
 
-    let starts: Vec<u64> = almanac.seeds.iter().skip(0).step_by(2).copied().collect();
+    let starts: Vec<u64> = almanac.seeds.iter().step_by(2).copied().collect();
     let ranges: Vec<u64> = almanac.seeds.iter().skip(1).step_by(2).copied().collect();
 
     let mut location = u64::MAX;
@@ -43,7 +75,7 @@ fn part2(input: &[String]) -> u64 {
     });
 
     //let location = locations.iter().min().unwrap();
-    location
+    Ok(location)
 }
 
 #[derive(Debug, Default)]
@@ -67,53 +99,57 @@ struct Almanac {
     humidity_to_location: Map,
 }
 
-fn get_almanac(input: &[String]) -> Almanac {
+fn get_almanac(input: &[String]) -> Result<Almanac, ParseError> {
     let mut almanac: Almanac = Default::default();
 
-    let chunks = input.split(|line| line == "");
-
-    for chunk in chunks {
-        if chunk[0].starts_with("seeds:") {
-            almanac.seeds = get_seeds(&chunk[0]);
-        } else if chunk[0].starts_with("seed-to-soil map:") {
-            parse_map(chunk, &mut almanac.seed_to_soil);
-        } else if chunk[0].starts_with("soil-to-fertilizer map:") {
-            parse_map(chunk, &mut almanac.soil_to_fertilizer);
-        } else if chunk[0].starts_with("fertilizer-to-water map:") {
-            parse_map(chunk, &mut almanac.fertilizer_to_water);
-        } else if chunk[0].starts_with("water-to-light map:") {
-            parse_map(chunk, &mut almanac.water_to_light);
-        } else if chunk[0].starts_with("light-to-temperature map:") {
-            parse_map(chunk, &mut almanac.light_to_temperature);
-        } else if chunk[0].starts_with("temperature-to-humidity map:") {
-            parse_map(chunk, &mut almanac.temperature_to_humidity);
-        } else if chunk[0].starts_with("humidity-to-location map:") {
-            parse_map(chunk, &mut almanac.humidity_to_location);
-        }
+    for (first, chunk) in common::parse::get_blocks(input) {
+        // errors of a block count their lines from its header
+        let shift = |err| common::parse::shift_lines(err, first + 1);
+
+        let map = match chunk[0].as_str() {
+            line if line.starts_with("seeds:") => {
+                almanac.seeds = get_seeds(line).map_err(shift)?;
+                continue;
+            }
+            "seed-to-soil map:" => &mut almanac.seed_to_soil,
+            "soil-to-fertilizer map:" => &mut almanac.soil_to_fertilizer,
+            "fertilizer-to-water map:" => &mut almanac.fertilizer_to_water,
+            "water-to-light map:" => &mut almanac.water_to_light,
+            "light-to-temperature map:" => &mut almanac.light_to_temperature,
+            "temperature-to-humidity map:" => &mut almanac.temperature_to_humidity,
+            "humidity-to-location map:" => &mut almanac.humidity_to_location,
+            line => {
+                return Err(shift(common::parse::get_error(
+                    line,
+                    0,
+                    "'seeds:' or a map name",
+                )))
+            }
+        };
+        parse_map(chunk, map).map_err(shift)?;
     }
 
-    //println!("{:?}\n", almanac);
+    if almanac.seeds.is_empty() {
+        let first = input.first().map_or("", String::as_str);
+        return Err(
+            common::parse::get_error(first, 0, "'seeds:' and at least one seed").at_line(1),
+        );
+    }
 
-    almanac
+    Ok(almanac)
 }
 
-fn parse_map(chunk: &[String], current_map: &mut Map) {
-    chunk
-        .iter()
-        .skip(1)
-        .for_each(|line| current_map.push(get_map_entrie(line)));
+fn parse_map(chunk: &[String], current_map: &mut Map) -> Result<(), ParseError> {
+    let pattern = Pattern::new("{} {} {}");
+    *current_map = common::parse::parse_lines(&chunk[1..], |line| get_map_entrie(&pattern, line))?;
+    Ok(())
 }
 
-fn get_seeds(line: &str) -> Vec<u64> {
-    let mut seeds: Vec<u64> = Vec::new();
-
-    line.split(": ").collect::<Vec<&str>>()[1]
-        .split(' ')
-        .collect::<Vec<&str>>()
-        .iter()
-        .for_each(|numberstr| seeds.push(numberstr.parse::<u64>().unwrap()));
-
-    seeds
+fn get_seeds(line: &str) -> Result<Vec<u64>, ParseError> {
+    match line.strip_prefix("seeds:") {
+        Some(_) => common::parse::get_numbers(line, "seeds:".len()..line.len()),
+        None => Err(common::parse::get_error(line, 0, "'seeds:'")),
+    }
 }
 
 fn get_corresponding(map: &Map, source: u64) -> u64 {
@@ -129,21 +165,20 @@ fn get_corresponding(map: &Map, source: u64) -> u64 {
             //     "source = {} -> destination = {}; entrie source = {}; entrie dest = {}",
             //     source, destination, entry.source, entry.destination
             // );
-            return;
         }
     });
     //println!("{} -> {}", source, destination);
     destination
 }
 
-fn get_map_entrie(line: &str) -> MapEntry {
-    let vec = line.split(' ').collect::<Vec<&str>>();
+fn get_map_entrie(pattern: &Pattern, line: &str) -> Result<MapEntry, ParseError> {
+    let captures = pattern.captures(line)?;
 
-    MapEntry {
-        destination: vec[0].parse::<u64>().unwrap(),
-        source: vec[1].parse::<u64>().unwrap(),
-        range: vec[2].parse::<u64>().unwrap(),
-    }
+    Ok(MapEntry {
+        destination: captures.get(0)?,
+        source: captures.get(1)?,
+        range: captures.get(2)?,
+    })
 }
 
 fn traverse_almanac(almanac: &Almanac, seed: u64) -> u64 {
@@ -162,30 +197,30 @@ mod tests {
     #[test]
     fn test_part1() {
         let input = get_test_input();
-        assert_eq!(super::part1(&input), 35);
+        assert_eq!(super::part1(&input), Ok(35));
     }
 
     #[test]
     fn test_part2() {
         let input = get_test_input();
-        assert_eq!(super::part2(&input), 46);
+        assert_eq!(super::part2(&input), Ok(46));
     }
 
     #[test]
     fn test_get_seeds() {
         let line: &str = "seeds: 79 14 55 13";
-        assert_eq!(super::get_seeds(line)[0], 79);
-        assert_eq!(super::get_seeds(line)[1], 14);
-        assert_eq!(super::get_seeds(line)[2], 55);
-        assert_eq!(super::get_seeds(line)[3], 13);
+        assert_eq!(super::get_seeds(line), Ok(vec![79, 14, 55, 13]));
+        assert!(super::get_seeds("seed: 79").is_err());
     }
 
     #[test]
     fn test_get_map_entrie() {
         let line: &str = "50 98 2";
-        assert_eq!(super::get_map_entrie(line).destination, 50);
-        assert_eq!(super::get_map_entrie(line).source, 98);
-        assert_eq!(super::get_map_entrie(line).range, 2);
+        let pattern = super::Pattern::new("{} {} {}");
+        let entry = super::get_map_entrie(&pattern, line).unwrap();
+        assert_eq!(entry.destination, 50);
+        assert_eq!(entry.source, 98);
+        assert_eq!(entry.range, 2);
     }
 
     #[test]
@@ -238,11 +273,32 @@ mod tests {
     #[test]
     fn test_traverse_almanac() {
         let input = get_test_input();
-        let almanac = super::get_almanac(&input);
+        let almanac = super::get_almanac(&input).unwrap();
         assert_eq!(super::traverse_almanac(&almanac, 79), 82);
         assert_eq!(super::traverse_almanac(&almanac, 14), 43);
     }
 
+    #[test]
+    fn test_get_almanac_errors() {
+        let mut input = get_test_input();
+        input[13] = "0 11 4x2".to_string();
+        assert_eq!(
+            super::get_almanac(&input).unwrap_err().in_file("input.txt").to_string(),
+            "error: expected u64, found '4x2'\n  --> input.txt:14:6\n   |\n14 | 0 11 4x2\n   |      ^^^"
+        );
+
+        input[11] = "fertilizer-to-wter map:".to_string();
+        let err = super::get_almanac(&input).unwrap_err();
+        assert_eq!((err.line, err.column), (12, 1));
+        assert_eq!(err.expected, "'seeds:' or a map name");
+        assert_eq!(err.found, "fertilizer-to-wter");
+
+        assert_eq!(
+            super::get_almanac(&get_test_input()[1..]).unwrap_err().line,
+            1
+        );
+    }
+
     fn get_test_input() -> Vec<String> {
         vec![
             "seeds: 79 14 55 13".to_string(),
@@ -1,82 +1,159 @@
-use std::fmt::Debug;
-
-use common;
+use common::input::InputFiles;
+use common::parse::{get_error, get_span_error, ParseError};
 
 fn main() {
-    println!("Part 1: {}", part1(common::read_lines("./input1.txt")));
-    println!("Part 2: {}", part2(common::read_lines("./input2.txt")));
+    let files = InputFiles::new(env!("CARGO_MANIFEST_DIR"), "input1.txt", "input2.txt");
+
+    if common::bench::requested() {
+        bench(&files);
+        return;
+    }
+
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let paths = common::input::take_paths(&mut args).unwrap_or_else(|err| {
+        eprintln!("error: {}", err);
+        std::process::exit(1);
+    });
+
+    let solved = files.run(&paths, |input1, input2| -> Result<(), ParseError> {
+        let part1 = part1(input1.lines.clone()).map_err(|err| err.in_file(&input1.name))?;
+        println!("Part 1: {}", part1);
+        let part2 = part2(input2.lines.clone()).map_err(|err| err.in_file(&input2.name))?;
+        println!("Part 2: {}", part2);
+        Ok(())
+    });
+    if !solved {
+        std::process::exit(1);
+    }
 }
 
-fn part1(input: Vec<String>) -> i64 {
-    let input_clean = input
+fn bench(files: &InputFiles) {
+    let (input1, input2) = files.read_lines();
+
+    print!(
+        "{}",
+        common::bench::Bench::new()
+            .run("parse", || get_races(&input1))
+            .run("part1", || part1(input1.clone()))
+            .run("part2", || part2(input2.clone()))
+            .report()
+    );
+}
+
+fn part1(input: Vec<String>) -> Result<i64, ParseError> {
+    Ok(get_races(&input)?
         .iter()
-        .map(|line| line.split_whitespace().skip(1).collect::<Vec<&str>>())
-        .collect::<Vec<Vec<&str>>>();
+        .map(|&(time, distance)| calc_winning(time, distance))
+        .product())
+}
 
-    let mut result: i64 = 1;
+fn part2(input: Vec<String>) -> Result<i64, ParseError> {
+    let (time, distance) = get_race(&input)?;
 
-    input_clean[0]
-        .iter()
-        .zip(input_clean[1].iter())
-        .for_each(|(time, distance)| {
-            result *= calc_winning(
-                time.parse::<u64>().unwrap(),
-                distance.parse::<u64>().unwrap(),
-            );
-        });
-
-    result
+    Ok(calc_winning(time, distance))
 }
 
-fn part2(input: Vec<String>) -> i64 {
-    let input_clean = input
-        .iter()
-        .map(|line| line.replace(" ", ""))
-        .collect::<Vec<String>>();
+// "Time:" and "Distance:" followed by the numbers, the line number is the index + 1.
+fn get_field<'a>(
+    input: &'a [String],
+    index: usize,
+    name: &str,
+) -> Result<(&'a str, usize), ParseError> {
+    let line = input.get(index).map_or("", String::as_str);
+    let start = line.len() - line.trim_start().len();
+
+    match line[start..].strip_prefix(name) {
+        Some(_) => Ok((line, start + name.len())),
+        None => Err(get_error(line, start, &format!("'{}'", name)).at_line(index + 1)),
+    }
+}
 
-    let input_split = input_clean
-        .iter()
-        .map(|line| line.split(":").skip(1).collect::<Vec<&str>>())
-        .collect::<Vec<Vec<&str>>>();
+// (time, distance) of every race
+fn get_races(input: &[String]) -> Result<Vec<(u64, u64)>, ParseError> {
+    let (times, start) = get_field(input, 0, "Time:")?;
+    let times: Vec<u64> =
+        common::parse::get_numbers(times, start..times.len()).map_err(|err| err.at_line(1))?;
+    let (line, start) = get_field(input, 1, "Distance:")?;
+    let distances: Vec<u64> =
+        common::parse::get_numbers(line, start..line.len()).map_err(|err| err.at_line(2))?;
+
+    if times.len() != distances.len() {
+        let expected = format!("{} distances, one per race", times.len());
+        return Err(get_error(line, line.len(), &expected).at_line(2));
+    }
 
-    calc_winning(
-        input_split[0][0].parse::<u64>().unwrap(),
-        input_split[1][0].parse::<u64>().unwrap(),
-    )
+    Ok(times.into_iter().zip(distances).collect())
+}
+
+// The kerning is bad, all numbers of a line are one number.
+fn get_race(input: &[String]) -> Result<(u64, u64), ParseError> {
+    let number = |index: usize, name: &str| -> Result<u64, ParseError> {
+        let (line, start) = get_field(input, index, name)?;
+        line[start..]
+            .replace(' ', "")
+            .parse::<u64>()
+            .map_err(|_| get_span_error(line, start, line.len(), "a number").at_line(index + 1))
+    };
+
+    Ok((number(0, "Time:")?, number(1, "Distance:")?))
 }
 
 fn calc_winning(time: u64, distance: u64) -> i64 {
     let isqrt: f64 = (((time.pow(2) as f64) / 4.0) - distance as f64).sqrt();
-    let winning_lower: i64 = ((time as f64) / 2.0 - isqrt).ceil() as i64;
-    let winning_higher: i64 = ((time as f64) / 2.0 + isqrt).floor() as i64;
+    // the record has to be beaten, holding exactly as long as a root only ties it
+    let winning_lower: i64 = ((time as f64) / 2.0 - isqrt).floor() as i64 + 1;
+    let winning_higher: i64 = ((time as f64) / 2.0 + isqrt).ceil() as i64 - 1;
 
-    winning_higher - winning_lower + 1
+    (winning_higher - winning_lower + 1).max(0)
 }
 
 #[cfg(test)]
 mod tests {
+    include!(concat!(env!("OUT_DIR"), "/readme_examples.rs"));
 
     fn get_part1_test_input() -> Vec<String> {
-        vec![
-            "Time:      7  15   30".to_string(),
-            "Distance:  9  40  200".to_string(),
-        ]
+        get_example_input(1)
     }
 
     #[test]
     fn test_part1() {
-        assert_eq!(super::part1(get_part1_test_input()), 352);
+        assert_eq!(super::part1(get_part1_test_input()), Ok(288));
     }
 
     #[test]
     fn test_calc_winning() {
         assert_eq!(super::calc_winning(7, 9), 4);
         assert_eq!(super::calc_winning(15, 40), 8);
-        assert_eq!(super::calc_winning(30, 200), 11);
+        assert_eq!(super::calc_winning(30, 200), 9);
+    }
+
+    #[test]
+    fn test_calc_winning_ties() {
+        // holding 2ms of 4ms goes exactly 4mm, which ties the record but does not beat it
+        assert_eq!(super::calc_winning(4, 4), 0);
+        assert_eq!(super::calc_winning(4, 3), 1);
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(super::part2(get_part1_test_input()), 71503);
+        assert_eq!(super::part2(get_part1_test_input()), Ok(71503));
+    }
+
+    #[test]
+    fn test_get_races_errors() {
+        let mut input = get_part1_test_input();
+        input[1] = "Distance:  9  40".to_string();
+        let err = super::get_races(&input).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 17));
+        assert_eq!(err.expected, "3 distances, one per race");
+
+        input[0] = "Tme:      7  15   30".to_string();
+        let err = super::part1(input.clone()).unwrap_err();
+        assert_eq!((err.line, err.column, err.found.as_str()), (1, 1, "Tme:"));
+
+        input[0] = "Time:      7  15  3O".to_string();
+        let err = super::part2(input).unwrap_err();
+        assert_eq!((err.line, err.column, err.width), (1, 6, 15));
+        assert!(super::part1(vec!["Time: 7".to_string()]).is_err());
     }
 }
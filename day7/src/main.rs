@@ -1,8 +1,9 @@
+use common::input::InputFiles;
+use common::parse::{get_span_error, ParseError, Pattern};
 use std::fmt::Debug;
 
 #[derive(Debug)]
 struct Hand {
-    #[allow(dead_code)]
     cards: [char; 5],
     bid: u64,
     value: u64,
@@ -10,12 +11,46 @@ struct Hand {
 }
 
 fn main() {
-    println!("Part 1: {}", part1(common::read_lines("./input1.txt")));
-    println!("Part 2: {}", part2(common::read_lines("./input2.txt")));
+    let files = InputFiles::new(env!("CARGO_MANIFEST_DIR"), "input1.txt", "input2.txt");
+
+    if common::bench::requested() {
+        bench(&files);
+        return;
+    }
+
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let paths = common::input::take_paths(&mut args).unwrap_or_else(|err| {
+        eprintln!("error: {}", err);
+        std::process::exit(1);
+    });
+
+    let solved = files.run(&paths, |input1, input2| -> Result<(), ParseError> {
+        let part1 = part1(input1.lines.clone()).map_err(|err| err.in_file(&input1.name))?;
+        println!("Part 1: {}", part1);
+        let part2 = part2(input2.lines.clone()).map_err(|err| err.in_file(&input2.name))?;
+        println!("Part 2: {}", part2);
+        Ok(())
+    });
+    if !solved {
+        std::process::exit(1);
+    }
 }
 
-fn part1(input: Vec<String>) -> u64 {
-    let mut hands = get_hands(input);
+fn bench(files: &InputFiles) {
+    let (input1, input2) = files.read_lines();
+
+    print!(
+        "{}",
+        common::bench::Bench::new()
+            .run("parse", || get_hands(&input1))
+            .run("part1", || part1(input1.clone()))
+            .run("part2", || part2(input2.clone()))
+            .report()
+    );
+}
+
+fn part1(input: Vec<String>) -> Result<u64, ParseError> {
+    let mut hands = get_hands(&input)?;
     let mut points: u64 = 0;
     let mut multiplier: u64 = 1;
 
@@ -27,11 +62,11 @@ fn part1(input: Vec<String>) -> u64 {
         hand.rank = multiplier;
     }
 
-    points
+    Ok(points)
 }
 
-fn part2(input: Vec<String>) -> u64 {
-    let mut hands = get_hands2(input);
+fn part2(input: Vec<String>) -> Result<u64, ParseError> {
+    let mut hands = get_hands2(&input)?;
     let mut points: u64 = 0;
     let mut multiplier: u64 = 1;
 
@@ -43,42 +78,55 @@ fn part2(input: Vec<String>) -> u64 {
         hand.rank = multiplier;
     }
 
-    points
+    Ok(points)
 }
 
-fn get_hands(input: Vec<String>) -> Vec<Hand> {
-    let mut hands: Vec<Hand> = Vec::new();
-    for line in input {
-        hands.push(get_hand(line));
-    }
+fn get_hands(input: &[String]) -> Result<Vec<Hand>, ParseError> {
+    let pattern = Pattern::new("{} {}");
 
-    hands
+    common::parse::parse_lines(input, |line| get_hand(&pattern, line))
 }
 
-fn get_hands2(mut input: Vec<String>) -> Vec<Hand> {
-    let mut hands: Vec<Hand> = Vec::new();
-
-    input.iter_mut().for_each(|line| {
-        *line = line.replace('J', "1");
-        hands.push(get_hand(line.to_string()));
-    });
-
-    hands
+fn get_hands2(input: &[String]) -> Result<Vec<Hand>, ParseError> {
+    Ok(get_hands(input)?.into_iter().map(with_jokers).collect())
 }
 
-fn get_hand(line: String) -> Hand {
-    let mut cards: [char; 5] = ['0'; 5];
-    let line_split = line.split_whitespace().collect::<Vec<&str>>();
+// "32T3K 765", five cards and the bid
+fn get_hand(pattern: &Pattern, line: &str) -> Result<Hand, ParseError> {
+    let captures = pattern.captures(line)?;
+    let hand = captures.get_str(0);
 
-    for (i, c) in line_split[0].chars().enumerate() {
+    let mut cards: [char; 5] = ['0'; 5];
+    for (i, (start, c)) in hand.char_indices().enumerate() {
+        if i == cards.len() {
+            return Err(get_span_error(line, 0, hand.len(), "five cards"));
+        }
+        if !"23456789TJQKA".contains(c) {
+            let expected = "a card (2-9, T, J, Q, K or A)";
+            return Err(get_span_error(line, start, start + c.len_utf8(), expected));
+        }
         cards[i] = c;
     }
+    if hand.len() < cards.len() {
+        return Err(get_span_error(line, 0, hand.len(), "five cards"));
+    }
 
-    Hand {
+    Ok(Hand {
         cards,
-        bid: line_split[1].parse::<u64>().unwrap(),
+        bid: captures.get(1)?,
         value: get_hand_value(cards),
         rank: 0,
+    })
+}
+
+// Part 2 reads every J as a joker, written '1' so it is the weakest card.
+fn with_jokers(hand: Hand) -> Hand {
+    let cards = hand.cards.map(|c| if c == 'J' { '1' } else { c });
+
+    Hand {
+        cards,
+        value: get_hand_value(cards),
+        ..hand
     }
 }
 
@@ -147,31 +195,27 @@ fn get_card_value(c: char) -> u64 {
 
 #[cfg(test)]
 mod tests {
+    include!(concat!(env!("OUT_DIR"), "/readme_examples.rs"));
 
     fn get_part1_test_input() -> Vec<String> {
-        vec![
-            "32T3K 765".to_string(),
-            "T55J5 684".to_string(),
-            "KK677 28".to_string(),
-            "KTJJT 220".to_string(),
-            "QQQJA 483".to_string(),
-        ]
+        get_example_input(1)
     }
 
     #[test]
     fn test_part1() {
-        assert_eq!(super::part1(get_part1_test_input()), 6440);
+        assert_eq!(super::part1(get_part1_test_input()), Ok(6440));
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(super::part2(get_part1_test_input()), 5905);
+        assert_eq!(super::part2(get_part1_test_input()), Ok(5905));
     }
 
     #[test]
     fn test_get_hand() {
         //Test without wildcards
-        let hand = super::get_hand("32T3K 765".to_string());
+        let pattern = super::Pattern::new("{} {}");
+        let hand = super::get_hand(&pattern, "32T3K 765").unwrap();
 
         println!("{:?}", hand);
 
@@ -179,7 +223,7 @@ mod tests {
         assert_eq!(hand.value, 20302100313);
 
         //Test with wildcards
-        let hand2 = super::get_hand("3213K 666".to_string());
+        let hand2 = super::with_jokers(super::get_hand(&pattern, "32J3K 666").unwrap());
 
         println!("{:?}", hand2);
 
@@ -187,6 +231,38 @@ mod tests {
         assert_eq!(hand2.value, 40302010313);
     }
 
+    #[test]
+    fn test_get_hand_errors() {
+        let pattern = super::Pattern::new("{} {}");
+        let error = |line: &str| {
+            let err = super::get_hand(&pattern, line).unwrap_err();
+            (err.column, err.width, err.expected, err.found)
+        };
+
+        assert_eq!(
+            error("32X3K 765"),
+            (
+                3,
+                1,
+                "a card (2-9, T, J, Q, K or A)".to_string(),
+                "X".to_string()
+            )
+        );
+        assert_eq!(
+            error("32T3 765"),
+            (1, 4, "five cards".to_string(), "32T3".to_string())
+        );
+        assert_eq!(
+            error("32T3KK 765"),
+            (1, 6, "five cards".to_string(), "32T3KK".to_string())
+        );
+        assert_eq!(
+            error("32T3K 7a5"),
+            (7, 3, "u64".to_string(), "7a5".to_string())
+        );
+        assert_eq!(error("32T3K"), (6, 1, "' '".to_string(), "".to_string()));
+    }
+
     #[test]
     fn test_get_type_value() {
         assert_eq!(super::get_type_value(['A', 'A', 'A', 'A', 'A']), 7);
@@ -1,6 +1,9 @@
 use std::collections::HashMap;
 use std::fmt::Debug;
 
+use common::input::InputFiles;
+use common::parse::{get_span_error, ParseError, Pattern};
+
 #[derive(Debug)]
 struct Direction {
     key: String,
@@ -9,79 +12,126 @@ struct Direction {
 }
 
 fn main() {
-    println!("Part 1: {}", part1(common::read_lines("./input1.txt")));
-    println!("Part 2: {}", part2(common::read_lines("./input2.txt")));
-}
-
-fn part1(mut input: Vec<String>) -> u64 {
-    let mut current_key = "AAA".to_string();
-    let mut step: usize = 0;
-
-    let instructions = input[0].chars().collect::<Vec<char>>();
-
-    input.remove(0);
-    input.remove(0);
-
-    let directions = get_directions(input);
+    let files = InputFiles::new(env!("CARGO_MANIFEST_DIR"), "input1.txt", "input2.txt");
 
-    while current_key != "ZZZ" {
-        let instruction = instructions[step % instructions.len() as usize];
-        let direction = directions.get(&current_key).unwrap();
+    if common::bench::requested() {
+        bench(&files);
+        return;
+    }
 
-        if instruction == 'R' {
-            current_key = direction.right.to_string();
-        } else {
-            current_key = direction.left.to_string();
-        }
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let paths = common::input::take_paths(&mut args).unwrap_or_else(|err| {
+        eprintln!("error: {}", err);
+        std::process::exit(1);
+    });
 
-        step += 1;
+    let solved = files.run(&paths, |input1, input2| -> Result<(), ParseError> {
+        let part1 = part1(input1.lines.clone()).map_err(|err| err.in_file(&input1.name))?;
+        println!("Part 1: {}", part1);
+        let part2 = part2(input2.lines.clone()).map_err(|err| err.in_file(&input2.name))?;
+        println!("Part 2: {}", part2);
+        Ok(())
+    });
+    if !solved {
+        std::process::exit(1);
     }
+}
 
-    step as u64
+fn bench(files: &InputFiles) {
+    let (input1, input2) = files.read_lines();
+
+    print!(
+        "{}",
+        common::bench::Bench::new()
+            .run("parse", || get_network(&input1))
+            .run("part1", || part1(input1.clone()))
+            .run("part2", || part2(input2.clone()))
+            .report()
+    );
 }
 
-fn part2(mut input: Vec<String>) -> u64 {
-    let mut current_keys = Vec::<String>::new();
-    let mut step = Vec::<u64>::new();
+fn part1(input: Vec<String>) -> Result<u64, ParseError> {
+    let (instructions, directions) = get_network(&input)?;
 
-    let instructions = input[0].chars().collect::<Vec<char>>();
+    get_steps(&instructions, &directions, "AAA", |key| key == "ZZZ")
+        .ok_or_else(|| get_node_error(&input, "AAA", "a node from which ZZZ can be reached"))
+}
 
-    input.remove(0);
-    input.remove(0);
+fn part2(input: Vec<String>) -> Result<u64, ParseError> {
+    let (instructions, directions) = get_network(&input)?;
 
-    let directions = get_directions(input);
+    let mut starts: Vec<&String> = directions.keys().filter(|key| key.ends_with('A')).collect();
+    if starts.is_empty() {
+        let line = input.last().map_or("", String::as_str);
+        return Err(
+            common::parse::get_error(line, line.len(), "a node ending in 'A'").at_line(input.len()),
+        );
+    }
+    starts.sort();
+
+    let step = starts
+        .into_iter()
+        .map(|key| {
+            get_steps(&instructions, &directions, key, |key| key.ends_with('Z')).ok_or_else(|| {
+                get_node_error(
+                    &input,
+                    key,
+                    "a node from which one ending in 'Z' can be reached",
+                )
+            })
+        })
+        .collect::<Result<Vec<u64>, ParseError>>()?;
+
+    Ok(lcm(step))
+}
 
-    directions.iter().for_each(|(key, _)| {
-        if key.ends_with("A") {
-            current_keys.push(key.to_string());
-            step.push(0);
-        }
+// Steps from start to the first node is_end accepts, following the instructions over and over,
+// None if they never get there. A node is a key and how far into the instructions we are, so
+// the search ends once the walk goes round in circles.
+fn get_steps<'a>(
+    instructions: &[char],
+    directions: &'a HashMap<String, Direction>,
+    start: &'a str,
+    is_end: impl Fn(&str) -> bool,
+) -> Option<u64> {
+    let search = common::search::bfs([(start, 0)], |(key, i): &(&'a str, usize)| {
+        directions.get(*key).map(|direction| {
+            let next = match instructions[*i] {
+                'R' => direction.right.as_str(),
+                _ => direction.left.as_str(),
+            };
+            (next, (i + 1) % instructions.len())
+        })
     });
 
-    for (i, key) in current_keys.iter_mut().enumerate() {
-        while !key.ends_with("Z") {
-            let instruction = instructions[step[i] as usize % instructions.len() as usize];
-            let direction = directions.get(key).unwrap();
+    search
+        .order
+        .iter()
+        .find(|(key, _)| is_end(key))
+        .and_then(|node| search.get_distance(node))
+}
 
-            if instruction == 'R' {
-                *key = direction.right.to_string();
-            } else {
-                *key = direction.left.to_string();
-            }
+// Points at the key of the node's line, or past the last line when the node is not defined.
+fn get_node_error(input: &[String], key: &str, expected: &str) -> ParseError {
+    let pattern = Pattern::new("{} = ({}, {})");
 
-            step[i] += 1;
+    for (i, line) in input.iter().enumerate().skip(1) {
+        if pattern
+            .captures(line)
+            .is_ok_and(|captures| captures.get_str(0) == key)
+        {
+            return common::parse::get_span_error(line, 0, key.len(), expected).at_line(i + 1);
         }
     }
 
-    lcm(step)
+    let line = input.last().map_or("", String::as_str);
+    common::parse::get_error(line, line.len(), &format!("a node {}", key)).at_line(input.len())
 }
 
 fn lcm(steps: Vec<u64>) -> u64 {
-    let mut result = steps[0];
-    for i in 1..steps.len() {
-        result = result * steps[i] / gcd(result, steps[i]);
-    }
-    result
+    steps
+        .into_iter()
+        .fold(1, |result, step| result * step / gcd(result, step))
 }
 
 fn gcd(mut a: u64, mut b: u64) -> u64 {
@@ -89,71 +139,85 @@ fn gcd(mut a: u64, mut b: u64) -> u64 {
         return a;
     }
     if b > a {
-        let temp = a;
-        a = b;
-        b = temp;
+        std::mem::swap(&mut a, &mut b);
     }
     while b > 0 {
         let temp = a;
         a = b;
         b = temp % b;
     }
-    return a;
+    a
 }
 
-fn get_direction(line: String) -> Direction {
-    let line_clean = line.replace("(", "").replace(")", "").replace(" ", "");
-    let parts: Vec<&str> = line_clean.split("=").collect();
-    let key = parts[0].to_string();
-    let value = parts[1].to_string();
-    let parts: Vec<&str> = value.split(",").collect();
-    let left = parts[0].to_string();
-    let right = parts[1].to_string();
+fn get_direction(pattern: &Pattern, line: &str) -> Result<Direction, ParseError> {
+    let captures = pattern.captures(line)?;
 
-    Direction { key, left, right }
+    Ok(Direction {
+        key: captures.get_str(0).to_string(),
+        left: captures.get_str(1).to_string(),
+        right: captures.get_str(2).to_string(),
+    })
 }
 
-fn get_directions(mut input: Vec<String>) -> HashMap<String, Direction> {
-    let mut directions: HashMap<String, Direction> = HashMap::new();
+// Every node the left and right of a node lead to has to have a line of its own.
+fn get_directions(input: &[String]) -> Result<HashMap<String, Direction>, ParseError> {
+    let pattern = Pattern::new("{} = ({}, {})");
 
-    input.iter_mut().for_each(|line| {
-        let direction = get_direction(line.to_string());
-        directions.insert(direction.key.to_string(), direction);
-    });
+    let directions: HashMap<String, Direction> =
+        common::parse::parse_lines(input, |line| get_direction(&pattern, line))?
+            .into_iter()
+            .map(|direction| (direction.key.to_string(), direction))
+            .collect();
+
+    for (i, line) in input.iter().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let captures = pattern.captures(line)?;
+        for field in 1..=2 {
+            if !directions.contains_key(captures.get_str(field)) {
+                let range = captures.get_range(field);
+                return Err(
+                    get_span_error(line, range.start, range.end, "a node that is defined")
+                        .at_line(i + 1),
+                );
+            }
+        }
+    }
 
-    directions
+    Ok(directions)
+}
+
+// The instructions on the first line, then a blank line and the nodes.
+fn get_network(input: &[String]) -> Result<(Vec<char>, HashMap<String, Direction>), ParseError> {
+    let instructions: Vec<char> = input
+        .first()
+        .map(|line| line.chars().collect())
+        .unwrap_or_default();
+    if let Some(i) = instructions.iter().position(|c| *c != 'L' && *c != 'R') {
+        return Err(common::parse::get_error(&input[0], i, "'L' or 'R'").at_line(1));
+    }
+    if instructions.is_empty() {
+        let line = input.first().map_or("", String::as_str);
+        return Err(common::parse::get_error(line, 0, "instructions of 'L' and 'R'").at_line(1));
+    }
+
+    let directions = get_directions(input.get(1..).unwrap_or_default())
+        .map_err(|err| common::parse::shift_lines(err, 1))?;
+
+    Ok((instructions, directions))
 }
 
 #[cfg(test)]
 mod tests {
+    include!(concat!(env!("OUT_DIR"), "/readme_examples.rs"));
 
     fn get_part1_test_input1() -> Vec<String> {
-        vec![
-            "RL".to_string(),
-            "".to_string(),
-            "AAA = (BBB, CCC)".to_string(),
-            "BBB = (DDD, EEE)".to_string(),
-            "CCC = (ZZZ, GGG)".to_string(),
-            "DDD = (DDD, DDD)".to_string(),
-            "EEE = (EEE, EEE)".to_string(),
-            "GGG = (GGG, GGG)".to_string(),
-            "ZZZ = (ZZZ, ZZZ)".to_string(),
-        ]
+        get_example_input(1)
     }
 
     fn get_part2_test_input() -> Vec<String> {
-        vec![
-            "LR".to_string(),
-            "".to_string(),
-            "11A = (11B, XXX)".to_string(),
-            "11B = (XXX, 11Z)".to_string(),
-            "11Z = (11B, XXX)".to_string(),
-            "22A = (22B, XXX)".to_string(),
-            "22B = (22C, 22C)".to_string(),
-            "22C = (22Z, 22Z)".to_string(),
-            "22Z = (22B, 22B)".to_string(),
-            "XXX = (XXX, XXX)".to_string(),
-        ]
+        get_example_input(3)
     }
 
     fn get_part1_test_input1_clean() -> Vec<String> {
@@ -164,30 +228,78 @@ mod tests {
     }
 
     fn get_part1_test_input2() -> Vec<String> {
-        vec![
-            "LLR".to_string(),
-            "".to_string(),
-            "AAA = (BBB, BBB)".to_string(),
-            "BBB = (AAA, ZZZ)".to_string(),
-            "ZZZ = (ZZZ, ZZZ)".to_string(),
-        ]
+        get_example_input(2)
     }
 
     #[test]
     fn test_part1() {
-        assert_eq!(super::part1(get_part1_test_input1()), 2);
-        assert_eq!(super::part1(get_part1_test_input2()), 6);
+        assert_eq!(super::part1(get_part1_test_input1()), Ok(2));
+        assert_eq!(super::part1(get_part1_test_input2()), Ok(6));
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(super::part2(get_part2_test_input()), 6);
+        assert_eq!(super::part2(get_part2_test_input()), Ok(6));
+    }
+
+    #[test]
+    fn test_get_steps() {
+        let (instructions, directions) = super::get_network(&get_part1_test_input2()).unwrap();
+        let steps =
+            |end: &str| super::get_steps(&instructions, &directions, "AAA", |key| key == end);
+
+        assert_eq!(steps("ZZZ"), Some(6));
+        assert_eq!(steps("AAA"), Some(0));
+        assert_eq!(steps("XXX"), None);
+    }
+
+    #[test]
+    fn test_unreachable() {
+        let mut input = get_part1_test_input2();
+        input[3] = "BBB = (AAA, AAA)".to_string();
+        let err = super::part1(input).unwrap_err();
+        assert_eq!((err.line, err.column, err.width), (3, 1, 3));
+        assert_eq!(
+            err.get_message(),
+            "expected a node from which ZZZ can be reached, found 'AAA'"
+        );
+
+        let err = super::part1(get_part2_test_input()).unwrap_err();
+        assert_eq!((err.line, err.column), (10, 17));
+        assert_eq!(
+            err.get_message(),
+            "expected a node AAA, found the end of the line"
+        );
+
+        let mut input = get_part2_test_input();
+        input[7] = "22C = (XXX, XXX)".to_string();
+        let err = super::part2(input.clone()).unwrap_err();
+        assert_eq!((err.line, err.column, err.width), (6, 1, 3));
+        assert_eq!(
+            err.get_message(),
+            "expected a node from which one ending in 'Z' can be reached, found '22A'"
+        );
+
+        input[2] = "11X = (11B, XXX)".to_string();
+        input[5] = "22X = (22B, XXX)".to_string();
+        let err = super::part2(input).unwrap_err();
+        assert_eq!((err.line, err.column), (10, 17));
+        assert_eq!(
+            err.get_message(),
+            "expected a node ending in 'A', found the end of the line"
+        );
+    }
+
+    #[test]
+    fn test_lcm() {
+        assert_eq!(super::lcm(vec![2, 3, 4]), 12);
+        assert_eq!(super::lcm(Vec::new()), 1);
     }
 
     #[test]
     fn test_get_direction() {
-        let line = "AAA = (BBB, CCC)".to_string();
-        let direction = super::get_direction(line);
+        let pattern = super::Pattern::new("{} = ({}, {})");
+        let direction = super::get_direction(&pattern, "AAA = (BBB, CCC)").unwrap();
 
         println!("{:?}", direction);
 
@@ -197,10 +309,11 @@ mod tests {
     }
 
     #[test]
+    #[allow(unused_mut)]
     fn test_get_directions() {
         let mut input = get_part1_test_input1_clean();
 
-        let directions = super::get_directions(input);
+        let directions = super::get_directions(&input).unwrap();
 
         let direction = directions.get("AAA").unwrap();
 
@@ -210,4 +323,46 @@ mod tests {
         assert_eq!(direction.left, "BBB");
         assert_eq!(direction.right, "CCC");
     }
+
+    #[test]
+    fn test_get_network() {
+        let mut input = get_part1_test_input2();
+        assert_eq!(super::get_network(&input).unwrap().0, vec!['L', 'L', 'R']);
+
+        input[4] = "ZZZ = (QQQ, ZZZ)".to_string();
+        let err = super::get_network(&input).unwrap_err();
+        assert_eq!((err.line, err.column, err.width), (5, 8, 3));
+        assert_eq!(
+            err.get_message(),
+            "expected a node that is defined, found 'QQQ'"
+        );
+        input[4] = "ZZZ = (ZZZ, ZZZ)".to_string();
+
+        input[3] = "BBB = (AAA ZZZ)".to_string();
+        assert_eq!(
+            super::get_network(&input).unwrap_err().to_string(),
+            [
+                "error: expected ', ', found the end of the line",
+                " --> <input>:4:16",
+                "  |",
+                "4 | BBB = (AAA ZZZ)",
+                "  |                ^",
+            ]
+            .join("\n")
+        );
+
+        input[0] = "LLX".to_string();
+        let err = super::get_network(&input).unwrap_err();
+        assert_eq!((err.line, err.column), (1, 3));
+        assert_eq!(err.get_message(), "expected 'L' or 'R', found 'X'");
+
+        input[0] = String::new();
+        let err = super::part1(input).unwrap_err();
+        assert_eq!((err.line, err.column), (1, 1));
+        assert_eq!(
+            err.get_message(),
+            "expected instructions of 'L' and 'R', found the end of the line"
+        );
+        assert!(super::get_network(&[]).is_err());
+    }
 }
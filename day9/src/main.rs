@@ -1,16 +1,49 @@
+use common::input::InputFiles;
+use common::parse::ParseError;
+
 fn main() {
-    println!("Part 1: {}", part1(common::read_lines("./input1.txt")));
-    println!("Part 2: {}", part2(common::read_lines("./input2.txt")));
+    let files = InputFiles::new(env!("CARGO_MANIFEST_DIR"), "input1.txt", "input2.txt");
+
+    if common::bench::requested() {
+        bench(&files);
+        return;
+    }
+
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let paths = common::input::take_paths(&mut args).unwrap_or_else(|err| {
+        eprintln!("error: {}", err);
+        std::process::exit(1);
+    });
+
+    let solved = files.run(&paths, |input1, input2| -> Result<(), ParseError> {
+        let part1 = part1(input1.lines.clone()).map_err(|err| err.in_file(&input1.name))?;
+        println!("Part 1: {}", part1);
+        let part2 = part2(input2.lines.clone()).map_err(|err| err.in_file(&input2.name))?;
+        println!("Part 2: {}", part2);
+        Ok(())
+    });
+    if !solved {
+        std::process::exit(1);
+    }
 }
 
-fn part1(input: Vec<String>) -> u64 {
+fn bench(files: &InputFiles) {
+    let (input1, input2) = files.read_lines();
+
+    print!(
+        "{}",
+        common::bench::Bench::new()
+            .run("parse", || get_histories(&input1))
+            .run("part1", || part1(input1.clone()))
+            .run("part2", || part2(input2.clone()))
+            .report()
+    );
+}
+
+fn part1(input: Vec<String>) -> Result<u64, ParseError> {
     let mut result: i64 = 0;
 
-    input.iter().for_each(|line| {
-        let numbers = line
-            .split_whitespace()
-            .map(|x| x.parse::<i64>().unwrap())
-            .collect::<Vec<i64>>();
+    get_histories(&input)?.iter().for_each(|numbers| {
         for (i, number) in numbers.iter().enumerate() {
             result += number
                 * comb(numbers.len() as i64, i as i64)
@@ -18,24 +51,27 @@ fn part1(input: Vec<String>) -> u64 {
         }
     });
 
-    result as u64
+    Ok(result as u64)
 }
 
-fn part2(input: Vec<String>) -> u64 {
+fn part2(input: Vec<String>) -> Result<u64, ParseError> {
     let mut result: i64 = 0;
 
-    input.iter().for_each(|line| {
-        let numbers = line
-            .split_whitespace()
-            .map(|x| x.parse::<i64>().unwrap())
-            .collect::<Vec<i64>>();
+    get_histories(&input)?.iter().for_each(|numbers| {
         for (i, number) in numbers.iter().enumerate() {
             result +=
                 number * comb(numbers.len() as i64, i as i64 + 1) * (-1_i32).pow(i as u32) as i64;
         }
     });
 
-    result as u64
+    Ok(result as u64)
+}
+
+// One history of values per line.
+fn get_histories(input: &[String]) -> Result<Vec<Vec<i64>>, ParseError> {
+    common::parse::parse_lines(input, |line| {
+        common::parse::get_numbers(line, 0..line.len())
+    })
 }
 
 pub fn comb(n: i64, k: i64) -> i64 {
@@ -59,12 +95,24 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        assert_eq!(super::part1(get_test_input()), 114);
+        assert_eq!(super::part1(get_test_input()), Ok(114));
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(super::part2(get_test_input()), 2);
+        assert_eq!(super::part2(get_test_input()), Ok(2));
+    }
+
+    #[test]
+    fn test_get_histories() {
+        let mut input = get_test_input();
+        input.insert(1, String::new());
+        assert_eq!(super::get_histories(&input).unwrap().len(), 3);
+
+        input[2] = "1 3 6 1O 15 21".to_string();
+        let err = super::get_histories(&input).unwrap_err();
+        assert_eq!((err.line, err.column, err.width), (3, 7, 2));
+        assert_eq!(err.get_message(), "expected i64, found '1O'");
     }
 
     #[test]